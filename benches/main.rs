@@ -5,9 +5,9 @@ pub fn canada_second_coord_component(c: &mut Criterion) -> Result<(), BenchmarkE
         Benchset::new("canada::second_coord_component", dataset::nativejson_canada())?
             .do_not_measure_file_load_time()
             .add_rsonpath_with_all_result_types("$.features[*].geometry.coordinates[*][*][1]")?
-            .finish();
+            .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -17,9 +17,9 @@ pub fn canada_coord_476_1446_1(c: &mut Criterion) -> Result<(), BenchmarkError>
         Benchset::new("canada::coord_476_1446_1", dataset::nativejson_canada())?
             .do_not_measure_file_load_time()
             .add_rsonpath_with_all_result_types("$..coordinates[476][1446][1]")?
-            .finish();
+            .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -29,9 +29,9 @@ pub fn canada_coord_slice_100_to_200(c: &mut Criterion) -> Result<(), BenchmarkE
         Benchset::new("canada::coord_slice_100_to_200", dataset::nativejson_canada())?
             .do_not_measure_file_load_time()
             .add_rsonpath_with_all_result_types("$..coordinates[100:201][*][*]")?
-            .finish();
+            .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -41,9 +41,9 @@ pub fn canada_coord_slice_overlapping(c: &mut Criterion) -> Result<(), Benchmark
         Benchset::new("canada::coord_slice_overlapping", dataset::nativejson_canada())?
             .do_not_measure_file_load_time()
             .add_rsonpath_with_all_result_types("$..coordinates[5::7][3::10][*]")?
-            .finish();
+            .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -53,9 +53,9 @@ pub fn citm_seat_category(c: &mut Criterion) -> Result<(), BenchmarkError> {
         Benchset::new("citm::seatCategoryId", dataset::nativejson_citm())?
             .do_not_measure_file_load_time()
             .add_rsonpath_with_all_result_types("$..seatCategoryId")?
-            .finish();
+            .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -64,9 +64,9 @@ pub fn ast_nested_inner(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("ast::nested_inner", dataset::ast())?
         .do_not_measure_file_load_time()
         .add_rsonpath_with_all_result_types("$..inner..inner..type.qualType")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -75,9 +75,73 @@ pub fn ast_deepest(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("ast::deepest", dataset::ast())?
         .do_not_measure_file_load_time()
         .add_rsonpath_with_all_result_types("$..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*..*")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
+
+    Ok(())
+}
+
+pub fn generated_deep_descendant(c: &mut Criterion) -> Result<(), BenchmarkError> {
+    let benchset = Benchset::new("generated::deep_descendant", dataset::generated_deep())?
+        .do_not_measure_file_load_time()
+        .add_rsonpath_with_all_result_types("$..k0")?
+        .finish()?;
+
+    benchset.run(c)?;
+
+    Ok(())
+}
+
+pub fn generated_escapes_all_nodes(c: &mut Criterion) -> Result<(), BenchmarkError> {
+    let benchset = Benchset::new("generated::escapes_all_nodes", dataset::generated_escapes())?
+        .do_not_measure_file_load_time()
+        .add_rsonpath_with_all_result_types("$..*")?
+        .finish()?;
+
+    benchset.run(c)?;
+
+    Ok(())
+}
+
+pub fn generated_logs_each_record(c: &mut Criterion) -> Result<(), BenchmarkError> {
+    let benchset = Benchset::new("generated::logs_each_record", dataset::generated_logs())?
+        .query_each_record()?
+        .do_not_measure_file_load_time()
+        .add_target(BenchTarget::Rsonpath("$..k1", ResultType::Full))?
+        .add_target(BenchTarget::Rsonpath("$..k1", ResultType::Count))?;
+    #[cfg(feature = "jsurfer")]
+    let benchset = benchset.add_target(BenchTarget::JSurfer("$..k1"))?;
+    let benchset = benchset
+        .add_target(BenchTarget::JsonpathRust("$..k1"))?
+        .add_target(BenchTarget::SerdeJsonPath("$..k1"))?
+        .finish()?;
+
+    benchset.run(c)?;
+
+    Ok(())
+}
+
+pub fn crossref_doi_scaling(c: &mut Criterion) -> Result<(), BenchmarkError> {
+    let family = BenchsetFamily::new("doi_scaling", dataset::crossref_family(), |benchset| {
+        benchset
+            .do_not_measure_file_load_time()
+            .add_rust_native_targets("$..DOI")
+    });
+
+    family.run(c)?;
+
+    Ok(())
+}
+
+pub fn generated_size_scaling(c: &mut Criterion) -> Result<(), BenchmarkError> {
+    let family = BenchsetFamily::new("k0_scaling", dataset::generated_size_family(), |benchset| {
+        benchset
+            .do_not_measure_file_load_time()
+            .add_rust_native_targets("$..k0")
+    });
+
+    family.run(c)?;
 
     Ok(())
 }
@@ -86,9 +150,9 @@ pub fn bestbuy_products_category_slice(c: &mut Criterion) -> Result<(), Benchmar
     let benchset = Benchset::new("bestbuy::products_category", dataset::pison_bestbuy_short())?
         .do_not_measure_file_load_time()
         .add_rsonpath_with_all_result_types("$.products[*].categoryPath[1:3].id")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -112,9 +176,9 @@ pub fn bestbuy_products_video_only(c: &mut Criterion) -> Result<(), BenchmarkErr
             BenchTarget::Rsonpath("$..videoChapters", ResultType::Full),
             "rsonpath_descendant_nodes",
         )?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -123,9 +187,9 @@ pub fn bestbuy_all_nodes(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("bestbuy::all_nodes", dataset::pison_bestbuy_short())?
         .do_not_measure_file_load_time()
         .add_rsonpath_with_all_result_types("$..*")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -134,9 +198,9 @@ pub fn google_map_routes(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("google_map::routes", dataset::pison_google_map_short())?
         .do_not_measure_file_load_time()
         .add_rsonpath_with_all_result_types("$[*].routes[*].legs[*].steps[*].distance.text")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -160,9 +224,9 @@ pub fn google_map_travel_modes(c: &mut Criterion) -> Result<(), BenchmarkError>
             BenchTarget::Rsonpath("$..available_travel_modes", ResultType::Full),
             "rsonpath_descendant_nodes",
         )?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -186,9 +250,9 @@ pub fn walmart_items_name(c: &mut Criterion) -> Result<(), BenchmarkError> {
             BenchTarget::Rsonpath("$..items_name", ResultType::Full),
             "rsonpath_descendant_nodes",
         )?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -212,9 +276,30 @@ pub fn twitter_metadata(c: &mut Criterion) -> Result<(), BenchmarkError> {
             BenchTarget::Rsonpath("$..count", ResultType::Full),
             "rsonpath_descendant_nodes",
         )?
-        .finish();
+        .finish()?;
+
+    benchset.run(c)?;
 
-    benchset.run(c);
+    Ok(())
+}
+
+pub fn twitter_compressed_stream(c: &mut Criterion) -> Result<(), BenchmarkError> {
+    let benchset = Benchset::new("twitter::compressed_stream", dataset::twitter())?
+        .do_not_measure_file_load_time()
+        .add_target(BenchTarget::RsonpathBuffered("$..count", ResultType::Count))?
+        .add_target(BenchTarget::RsonpathCompressed(
+            "$..count",
+            dataset::Compression::Gzip,
+            ResultType::Count,
+        ))?
+        .add_target(BenchTarget::RsonpathCompressed(
+            "$..count",
+            dataset::Compression::Gzip,
+            ResultType::Full,
+        ))?
+        .finish()?;
+
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -223,9 +308,9 @@ pub fn inner_array(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("inner_array", dataset::ast())?
         .do_not_measure_file_load_time()
         .add_rsonpath_with_all_result_types("$..inner[0]")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -234,9 +319,9 @@ pub fn user_second_mention_index(c: &mut Criterion) -> Result<(), BenchmarkError
     let benchset = Benchset::new("user_mentions_indices", dataset::twitter())?
         .do_not_measure_file_load_time()
         .add_rsonpath_with_all_result_types("$..entities.user_mentions[1]")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -245,9 +330,9 @@ pub fn all_first_index(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("all_first_index", dataset::twitter())?
         .do_not_measure_file_load_time()
         .add_rsonpath_with_all_result_types("$..[0]")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -261,6 +346,11 @@ benchsets!(
     citm_seat_category,
     ast_nested_inner,
     ast_deepest,
+    generated_deep_descendant,
+    generated_escapes_all_nodes,
+    generated_logs_each_record,
+    crossref_doi_scaling,
+    generated_size_scaling,
     bestbuy_products_category_slice,
     bestbuy_products_video_only,
     bestbuy_all_nodes,
@@ -270,5 +360,6 @@ benchsets!(
     user_second_mention_index,
     walmart_items_name,
     twitter_metadata,
+    twitter_compressed_stream,
     all_first_index
 );
@@ -5,9 +5,10 @@ fn az_shallow_tenant_ids(c: &mut Criterion) -> Result<(), BenchmarkError> {
         .do_not_measure_file_load_time()
         .measure_compilation_time()
         .add_rsonpath_with_all_result_types("$[*].tenantId")?
-        .finish();
+        .add_rsonpath_buffered("$[*].tenantId")?
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -17,9 +18,10 @@ fn az_recursive_tenant_ids(c: &mut Criterion) -> Result<(), BenchmarkError> {
         .do_not_measure_file_load_time()
         .measure_compilation_time()
         .add_rsonpath_with_all_result_types("$..tenantId")?
-        .finish();
+        .add_rsonpath_buffered("$..tenantId")?
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -29,9 +31,9 @@ fn az_first_ten_tenant_ids(c: &mut Criterion) -> Result<(), BenchmarkError> {
         .do_not_measure_file_load_time()
         .measure_compilation_time()
         .add_rsonpath_with_all_result_types("$[:10].tenantId")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -41,9 +43,9 @@ fn az_tenant_17(c: &mut Criterion) -> Result<(), BenchmarkError> {
         .do_not_measure_file_load_time()
         .measure_compilation_time()
         .add_rsonpath_with_all_result_types("$[17]")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -53,9 +55,9 @@ fn az_tenant_last(c: &mut Criterion) -> Result<(), BenchmarkError> {
         .do_not_measure_file_load_time()
         .measure_compilation_time()
         .add_rsonpath_with_all_result_types("$[83]")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -65,9 +67,9 @@ fn az_every_other_tenant(c: &mut Criterion) -> Result<(), BenchmarkError> {
         .do_not_measure_file_load_time()
         .measure_compilation_time()
         .add_rsonpath_with_all_result_types("$[::2]")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
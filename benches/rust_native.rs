@@ -4,9 +4,9 @@ pub fn ast_decl_inner(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("rust_native::ast::decl_inner", dataset::ast())?
         .measure_compilation_time()
         .add_rust_native_targets("$..decl.name")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -31,10 +31,33 @@ pub fn twitter_metadata(c: &mut Criterion) -> Result<(), BenchmarkError> {
             BenchTarget::SerdeJsonPath("$.search_metadata.count"),
             "serde_json_path_direct",
         )?
-        .add_target_with_id(BenchTarget::SerdeJsonPath("$..count"), "serde_json_path_descendant")?
-        .finish();
+        .add_target_with_id(BenchTarget::SerdeJsonPath("$..count"), "serde_json_path_descendant")?;
+    #[cfg(feature = "jsonpath_lib")]
+    let benchset = benchset
+        .add_target_with_id(
+            BenchTarget::JsonpathLib("$.search_metadata.count"),
+            "jsonpath_lib_direct",
+        )?
+        .add_target_with_id(BenchTarget::JsonpathLib("$..count"), "jsonpath_lib_descendant")?;
+    let benchset = benchset
+        .add_target_with_id(BenchTarget::Jaq("$.search_metadata.count"), "jaq_direct")?
+        .add_target_with_id(BenchTarget::Jaq("$..count"), "jaq_descendant")?
+        .finish()?;
+
+    benchset.run(c)?;
+
+    Ok(())
+}
 
-    benchset.run(c);
+pub fn twitter_parse_baseline(c: &mut Criterion) -> Result<(), BenchmarkError> {
+    let benchset = Benchset::new("rust_native::twitter::parse_baseline", dataset::twitter())?
+        .do_not_measure_file_load_time()
+        .add_target(BenchTarget::RsonpathMmap("$..count", ResultType::Full))?
+        .add_target(BenchTarget::SerdeJsonPath("$..count"))?
+        .add_serde_json_baselines("$..count")?
+        .finish()?;
+
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -43,9 +66,9 @@ fn az_tenant_last(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("rust_native::az_tenants::tenant_last", dataset::az_tenants())?
         .measure_compilation_time()
         .add_rust_native_targets("$[83]")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -64,10 +87,17 @@ fn az_tenant_ids(c: &mut Criterion) -> Result<(), BenchmarkError> {
         .add_target_with_id(BenchTarget::JsonpathRust("$[*].tenantId"), "jsonpath-rust_direct")?
         .add_target_with_id(BenchTarget::JsonpathRust("$..tenantId"), "jsonpath-rust_descendant")?
         .add_target_with_id(BenchTarget::SerdeJsonPath("$[*].tenantId"), "serde_json_path_direct")?
-        .add_target_with_id(BenchTarget::SerdeJsonPath("$..tenantId"), "serde_json_path_descendant")?
-        .finish();
-
-    benchset.run(c);
+        .add_target_with_id(BenchTarget::SerdeJsonPath("$..tenantId"), "serde_json_path_descendant")?;
+    #[cfg(feature = "jsonpath_lib")]
+    let benchset = benchset
+        .add_target_with_id(BenchTarget::JsonpathLib("$[*].tenantId"), "jsonpath_lib_direct")?
+        .add_target_with_id(BenchTarget::JsonpathLib("$..tenantId"), "jsonpath_lib_descendant")?;
+    let benchset = benchset
+        .add_target_with_id(BenchTarget::Jaq("$[*].tenantId"), "jaq_direct")?
+        .add_target_with_id(BenchTarget::Jaq("$..tenantId"), "jaq_descendant")?
+        .finish()?;
+
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -76,9 +106,9 @@ fn az_every_other_tenant(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("rust_native::az_tenant:every_other_tenant", dataset::az_tenants())?
         .measure_compilation_time()
         .add_rust_native_targets("$[::2]")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -87,9 +117,9 @@ fn az_first_ten_tenant_ids(c: &mut Criterion) -> Result<(), BenchmarkError> {
     let benchset = Benchset::new("rust_native::az_tenant::first_ten_tenant_ids", dataset::az_tenants())?
         .measure_compilation_time()
         .add_rust_native_targets("$[:10].tenantId")?
-        .finish();
+        .finish()?;
 
-    benchset.run(c);
+    benchset.run(c)?;
 
     Ok(())
 }
@@ -101,5 +131,6 @@ benchsets!(
     az_tenant_ids,
     az_every_other_tenant,
     az_first_ten_tenant_ids,
-    twitter_metadata
+    twitter_metadata,
+    twitter_parse_baseline
 );
@@ -0,0 +1,13 @@
+use rsonpath_benchmarks::framework::suite::{Suite, SUITE_PATH_ENV_VAR};
+use rsonpath_benchmarks::prelude::*;
+
+fn suite(c: &mut Criterion) -> Result<(), BenchmarkError> {
+    let Some(path) = std::env::var_os(SUITE_PATH_ENV_VAR) else {
+        eprintln!("{SUITE_PATH_ENV_VAR} is not set, no suite to run.");
+        return Ok(());
+    };
+
+    Suite::load(path)?.run(c)
+}
+
+benchsets!(suite_benches, suite);
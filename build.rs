@@ -1,13 +1,21 @@
-use eyre::{eyre, Context, Result};
+#[cfg(feature = "jsurfer")]
+use eyre::Context;
+#[cfg(any(feature = "jsurfer", feature = "jsonski"))]
+use eyre::{eyre, Result};
 use std::error::Error;
+#[cfg(feature = "jsurfer")]
 use std::process::Command;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    #[cfg(feature = "jsurfer")]
     setup_jsurfer()?;
+    #[cfg(feature = "jsonski")]
+    setup_jsonski()?;
 
     Ok(())
 }
 
+#[cfg(feature = "jsurfer")]
 fn setup_jsurfer() -> Result<()> {
     let gradlew_status = Command::new("./gradlew")
         .arg("shadowJar")
@@ -31,3 +39,36 @@ fn setup_jsurfer() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "jsonski")]
+fn setup_jsonski() -> Result<()> {
+    const SOURCES: &str = "./src/implementations/jsonski/src";
+    const SHIM: &str = "./src/implementations/jsonskiShim/ffi.cpp";
+
+    if !std::path::Path::new(SOURCES).join("QueryProcessor.cpp").exists() {
+        return Err(eyre!(
+            "JSONSki sources not found in {SOURCES}, run \
+             `git submodule update --init src/implementations/jsonski` \
+             or disable the `jsonski` feature"
+        ));
+    }
+
+    cc::Build::new()
+        .cpp(true)
+        .std("c++11")
+        .opt_level(3)
+        .flag("-mavx")
+        .flag("-mavx2")
+        .flag("-msse4.2")
+        .flag("-mpclmul")
+        .warnings(false)
+        .file(format!("{SOURCES}/RecordLoader.cpp"))
+        .file(format!("{SOURCES}/QueryProcessor.cpp"))
+        .file(SHIM)
+        .try_compile("jsonski")?;
+
+    println!("cargo:rerun-if-changed={SOURCES}");
+    println!("cargo:rerun-if-changed={SHIM}");
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+"""Reference external engine using Python's json module.
+
+Speaks the line protocol described in `src/implementations/external.rs`.
+Supports queries made of names, wildcards and non-negative indices
+in child and descendant segments, e.g. `$..products[*].name` or `$['a'][0]`.
+
+Usage: pathimpl -e external --external-command python3 --external-arg engines/python_json.py '$..a' file.json
+"""
+
+import json
+import re
+import sys
+import time
+
+SEGMENT = re.compile(
+    r"(\.\.|\.)(\*|[A-Za-z_][A-Za-z0-9_]*)|(\.\.)?\[(?:(\*)|(\d+)|'((?:[^'\\]|\\.)*)')\]"
+)
+
+
+def parse(query):
+    if not query.startswith("$"):
+        raise ValueError(f"unsupported query: {query}")
+    steps = []
+    position = 1
+    while position < len(query):
+        match = SEGMENT.match(query, position)
+        if match is None:
+            raise ValueError(f"unsupported query: {query}")
+        dots, shorthand, bracket_dots, star, index, quoted = match.groups()
+        if shorthand == "*" or star:
+            selector = ("wildcard", None)
+        elif shorthand is not None:
+            selector = ("name", shorthand)
+        elif index is not None:
+            selector = ("index", int(index))
+        else:
+            selector = ("name", json.loads('"' + quoted.replace("\\'", "'") + '"'))
+        steps.append(((dots or bracket_dots) == "..", selector))
+        position = match.end()
+    return steps
+
+
+def select(selector, node, out):
+    kind, argument = selector
+    if kind == "name" and isinstance(node, dict) and argument in node:
+        out.append(node[argument])
+    elif kind == "index" and isinstance(node, list) and argument < len(node):
+        out.append(node[argument])
+    elif kind == "wildcard" and isinstance(node, dict):
+        out.extend(node.values())
+    elif kind == "wildcard" and isinstance(node, list):
+        out.extend(node)
+
+
+def evaluate(steps, root):
+    current = [root]
+    for descendant, selector in steps:
+        selected = []
+        for node in current:
+            if not descendant:
+                select(selector, node, selected)
+                continue
+            stack = [node]
+            while stack:
+                value = stack.pop()
+                select(selector, value, selected)
+                if isinstance(value, dict):
+                    stack.extend(reversed(list(value.values())))
+                elif isinstance(value, list):
+                    stack.extend(reversed(value))
+        current = selected
+    return current
+
+
+def main():
+    handles = {}
+    next_handle = 0
+
+    def store(value):
+        nonlocal next_handle
+        next_handle += 1
+        handles[str(next_handle)] = value
+        return str(next_handle)
+
+    for line in sys.stdin:
+        command, _, argument = line.rstrip("\n").partition(" ")
+        try:
+            if command == "load":
+                with open(argument, encoding="utf-8") as file:
+                    response = store(json.load(file))
+            elif command == "load_record":
+                response = store(json.loads(argument))
+            elif command == "compile":
+                response = store(parse(argument))
+            elif command == "run":
+                query, file = argument.split(" ")
+                start = time.perf_counter_ns()
+                count = len(evaluate(handles[query], handles[file]))
+                response = f"{count} {time.perf_counter_ns() - start}"
+            elif command == "free":
+                del handles[argument]
+                response = None
+            elif command == "quit":
+                break
+            else:
+                raise ValueError(f"unknown command: {command}")
+        except Exception as error:  # pylint: disable=broad-except
+            print(f"err {error}".replace("\n", " "), flush=True)
+            continue
+        print("ok" if response is None else f"ok {response}", flush=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -1,25 +1,87 @@
 use hex_literal::hex;
+use lazy_static::lazy_static;
 use reqwest::blocking as reqwest;
 use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fmt::Display;
 use std::fs;
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
 use thiserror::Error;
 
+mod checksum_cache;
+mod compression;
+mod generator;
+
+use checksum_cache::FileStamp;
+pub use checksum_cache::STRICT_CHECKSUMS_ENV_VAR;
+pub use compression::Compression;
+pub use generator::{GeneratorConfig, KeyDistribution};
+
 type Sha256Digest = [u8; 32];
 
+/// Directory or base URL of a mirror with dataset sources, consulted before downloading from the original URLs.
+pub const MIRROR_ENV_VAR: &str = "RSONPATH_BENCH_DATASET_MIRROR";
+
 pub(crate) struct JsonFile {
     pub(crate) file_path: String,
     pub(crate) size_in_bytes: usize,
     checksum: Sha256Digest,
 }
 
+#[derive(Debug, Clone)]
 pub struct Dataset {
     name: &'static str,
     path: &'static str,
     source: DatasetSource,
     checksum: Sha256Digest,
+    size_in_bytes: Option<usize>,
+    format: DatasetFormat,
+    tags: &'static [DatasetTag],
+}
+
+/// Datasets of the same kind and growing size, benchmarked together to see how throughput scales with input size.
+#[derive(Debug, Clone)]
+pub struct DatasetFamily {
+    name: &'static str,
+    members: Vec<Dataset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatasetStatus {
+    Missing,
+    Valid {
+        size_in_bytes: usize,
+    },
+    /// The file does not have the expected size, detected without hashing it.
+    WrongSize {
+        expected_size_in_bytes: usize,
+        actual_size_in_bytes: usize,
+    },
+    Corrupted {
+        actual_checksum: String,
+    },
+}
+
+/// Layout of the dataset file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetFormat {
+    /// A single JSON document.
+    Json,
+    /// Newline-delimited JSON, one record per line. Benchmarked with
+    /// [`Benchset::query_each_record`](crate::framework::Benchset::query_each_record).
+    JsonLines,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetTag {
+    Pison,
+    NativeJson,
+    Crossref,
+    Small,
+    Generated,
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +90,8 @@ pub enum DatasetSource {
     UrlJson(&'static str),
     UrlArchive(DatasetArchive),
     UrlTarArchive(DatasetArchive, &'static str),
+    /// Synthetic document produced by the generator, see [`GeneratorConfig`].
+    Generated(GeneratorConfig),
 }
 
 #[derive(Debug, Clone)]
@@ -37,9 +101,19 @@ pub struct DatasetArchive {
 }
 
 impl DatasetSource {
-    fn url(&self) -> Option<&'static str> {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::LocalJson => "local",
+            Self::UrlJson(_) => "url_json",
+            Self::UrlArchive(_) => "url_archive",
+            Self::UrlTarArchive(_, _) => "url_tar_archive",
+            Self::Generated(_) => "generated",
+        }
+    }
+
+    pub fn url(&self) -> Option<&'static str> {
         match self {
-            Self::LocalJson => None,
+            Self::LocalJson | Self::Generated(_) => None,
             Self::UrlJson(url) => Some(url),
             Self::UrlArchive(archive) | Self::UrlTarArchive(archive, _) => Some(archive.url),
         }
@@ -56,7 +130,244 @@ impl DatasetArchive {
     }
 }
 
+impl JsonFile {
+    pub(crate) fn checksum_hex(&self) -> String {
+        format_hex_string(&self.checksum).to_string()
+    }
+}
+
+impl DatasetFamily {
+    /// Members should be ordered from the smallest to the largest.
+    pub fn new(name: &'static str, members: Vec<Dataset>) -> Self {
+        Self { name, members }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn members(&self) -> &[Dataset] {
+        &self.members
+    }
+}
+
 impl Dataset {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+
+    pub fn source(&self) -> &DatasetSource {
+        &self.source
+    }
+
+    pub fn checksum_hex(&self) -> String {
+        format_hex_string(&self.checksum).to_string()
+    }
+
+    /// Expected size of the JSON file, if known ahead of time.
+    pub fn size_in_bytes(&self) -> Option<usize> {
+        self.size_in_bytes
+    }
+
+    pub fn format(&self) -> DatasetFormat {
+        self.format
+    }
+
+    pub fn tags(&self) -> &'static [DatasetTag] {
+        self.tags
+    }
+
+    pub fn has_tag(&self, tag: DatasetTag) -> bool {
+        self.tags.contains(&tag)
+    }
+
+    pub fn is_present(&self) -> bool {
+        self.json_path().exists()
+    }
+
+    /// Whether a compressed copy of the dataset is on disk, see [`Dataset::compress`].
+    pub fn is_present_compressed(&self) -> bool {
+        Compression::ALL
+            .into_iter()
+            .any(|c| Path::new(&self.compressed_path(c)).exists())
+    }
+
+    /// Make sure the dataset is on disk and valid, downloading it if needed.
+    pub fn fetch(&self) -> Result<usize, DatasetError> {
+        self.file_path().map(|f| f.size_in_bytes)
+    }
+
+    /// Check the dataset file against its size, if known, and its checksum without downloading anything.
+    pub fn verify(&self) -> Result<DatasetStatus, DatasetError> {
+        let stamp = FileStamp::read(self.json_path()).map_err(DatasetError::FileSystemError)?;
+        if let (Some(expected), Some(stamp)) = (self.size_in_bytes, stamp) {
+            if stamp.size_in_bytes() != expected {
+                return Ok(DatasetStatus::WrongSize {
+                    expected_size_in_bytes: expected,
+                    actual_size_in_bytes: stamp.size_in_bytes(),
+                });
+            }
+        }
+
+        Ok(match self.load_file()? {
+            None => DatasetStatus::Missing,
+            Some(json_file) if json_file.checksum == self.checksum => DatasetStatus::Valid {
+                size_in_bytes: json_file.size_in_bytes,
+            },
+            Some(json_file) => DatasetStatus::Corrupted {
+                actual_checksum: json_file.checksum_hex(),
+            },
+        })
+    }
+
+    /// Remove the downloaded dataset files and their compressed copies. Returns whether anything was removed.
+    ///
+    /// Local datasets are part of the repository and are never removed.
+    pub fn clean(&self) -> Result<bool, DatasetError> {
+        if let DatasetSource::LocalJson = self.source {
+            return Ok(false);
+        }
+
+        let compressed_paths = Compression::ALL.map(|c| PathBuf::from(self.compressed_path(c)));
+        let mut removed = false;
+        for path in [self.json_path().to_owned(), self.archive_path()]
+            .into_iter()
+            .chain(compressed_paths)
+        {
+            match fs::remove_file(path) {
+                Ok(()) => removed = true,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+                Err(err) => return Err(DatasetError::FileSystemError(err)),
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Remove the JSON file, keeping other files such as compressed copies. Returns whether it was removed.
+    ///
+    /// Local datasets are part of the repository and are never removed.
+    pub fn remove_json(&self) -> Result<bool, DatasetError> {
+        if let DatasetSource::LocalJson = self.source {
+            return Ok(false);
+        }
+
+        match fs::remove_file(self.json_path()) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(DatasetError::FileSystemError(err)),
+        }
+    }
+
+    /// Path of the compressed copy of the dataset, see [`Dataset::compress`].
+    pub fn compressed_path(&self, compression: Compression) -> String {
+        compression.compressed_path(self.path)
+    }
+
+    /// Make sure a valid compressed copy of the dataset exists next to its JSON file, compressing the JSON if needed.
+    /// Returns the size of the compressed file.
+    ///
+    /// Once a compressed copy exists, the JSON file can be removed. It is restored from the copy
+    /// instead of being downloaded again whenever it is needed.
+    pub fn compress(&self, compression: Compression) -> Result<usize, DatasetError> {
+        if !compression.is_supported() {
+            return Err(DatasetError::UnsupportedCompression(compression));
+        }
+        if let Some(size_in_bytes) = self.load_compressed(compression)? {
+            return Ok(size_in_bytes);
+        }
+
+        let json_file = self.file_path()?;
+        let path = self.compressed_path(compression);
+        let tmp_path = format!("{path}.tmp");
+        let reader = io::BufReader::new(fs::File::open(&json_file.file_path).map_err(DatasetError::FileSystemError)?);
+        let writer = io::BufWriter::new(fs::File::create(&tmp_path).map_err(DatasetError::FileSystemError)?);
+
+        let progress = get_progress_bar("Compressing", Some(json_file.size_in_bytes as u64));
+        compression
+            .compress(progress.wrap_read(reader), writer)
+            .and_then(|mut w| w.flush())
+            .map_err(DatasetError::InputOutputError)?;
+        progress.finish_and_clear();
+        fs::rename(&tmp_path, &path).map_err(DatasetError::FileSystemError)?;
+
+        let stamp = FileStamp::read(path.as_ref())
+            .map_err(DatasetError::FileSystemError)?
+            .ok_or(DatasetError::InvalidPath(self.path))?;
+        checksum_cache::record(&path, stamp, self.checksum);
+
+        Ok(stamp.size_in_bytes())
+    }
+
+    /// Check the compressed copy by hashing its decompressed contents.
+    /// Returns the size of the compressed file if it is present and valid.
+    fn load_compressed(&self, compression: Compression) -> Result<Option<usize>, DatasetError> {
+        let path = self.compressed_path(compression);
+        let Some(stamp) = FileStamp::read(path.as_ref()).map_err(DatasetError::FileSystemError)? else {
+            return Ok(None);
+        };
+
+        // The cache holds the checksum of the decompressed contents for compressed copies.
+        let checksum = match checksum_cache::lookup(&path, &stamp) {
+            Some(checksum) => checksum,
+            None => {
+                let file = fs::File::open(&path).map_err(DatasetError::FileSystemError)?;
+                let progress = get_progress_bar(
+                    "Checking compressed dataset integrity...",
+                    Some(stamp.size_in_bytes() as u64),
+                );
+                let decoder = compression
+                    .decoder(progress.wrap_read(io::BufReader::new(file)))
+                    .map_err(DatasetError::InputOutputError)?;
+                let result = read_digest_and_write::<_, fs::File>(decoder, None);
+                progress.finish_and_clear();
+                match result {
+                    Ok((checksum, _)) => {
+                        checksum_cache::record(&path, stamp, checksum);
+                        checksum
+                    }
+                    Err(err) => {
+                        eprintln!("Compressed copy of dataset {} cannot be decompressed: {err}", self.name);
+                        return Ok(None);
+                    }
+                }
+            }
+        };
+
+        Ok((checksum == self.checksum).then_some(stamp.size_in_bytes()))
+    }
+
+    /// Decompress the JSON file from a valid local compressed copy, if there is one.
+    fn restore_from_compressed(&self) -> Result<Option<JsonFile>, DatasetError> {
+        for compression in Compression::ALL.into_iter().filter(Compression::is_supported) {
+            if self.load_compressed(compression)?.is_none() {
+                continue;
+            }
+
+            eprintln!("Restoring dataset {} from its {compression} copy.", self.name);
+            let file = fs::File::open(self.compressed_path(compression)).map_err(DatasetError::FileSystemError)?;
+            let decoder = compression
+                .decoder(io::BufReader::new(file))
+                .map_err(DatasetError::InputOutputError)?;
+            let mut target =
+                io::BufWriter::new(fs::File::create(self.json_path()).map_err(DatasetError::FileSystemError)?);
+            let (checksum, size_in_bytes) = read_digest_and_write(decoder, Some(&mut target))?;
+            target.flush().map_err(DatasetError::InputOutputError)?;
+
+            return Ok(Some(JsonFile {
+                file_path: self.path.to_string(),
+                checksum,
+                size_in_bytes,
+            }));
+        }
+
+        Ok(None)
+    }
+
     pub(crate) fn file_path(&self) -> Result<JsonFile, DatasetError> {
         match self.load_file()? {
             Some(json_file) if self.checksum == json_file.checksum => return Ok(json_file),
@@ -72,9 +383,19 @@ impl Dataset {
                 eprintln!("File for dataset {} does not exist.", self.name);
             }
         }
-        let new_json_file = self.download_file()?;
-
-        if new_json_file.checksum != self.checksum {
+        let new_json_file = match self.restore_from_compressed()? {
+            Some(json_file) => json_file,
+            None => self.download_file()?,
+        };
+        self.record_checksum(&new_json_file);
+
+        if new_json_file.checksum != self.checksum && matches!(self.source, DatasetSource::Generated(_)) {
+            Err(DatasetError::InvalidGeneratedChecksum(
+                self.name,
+                self.checksum,
+                new_json_file.checksum,
+            ))
+        } else if new_json_file.checksum != self.checksum {
             Err(DatasetError::InvalidJsonChecksum(
                 self.source.url().unwrap_or("None"),
                 self.checksum,
@@ -102,15 +423,29 @@ impl Dataset {
     }
 
     fn load_file(&self) -> Result<Option<JsonFile>, DatasetError> {
+        let Some(stamp) = FileStamp::read(self.json_path()).map_err(DatasetError::FileSystemError)? else {
+            return Ok(None);
+        };
+
+        if let Some(checksum) = checksum_cache::lookup(self.path, &stamp) {
+            return Ok(Some(JsonFile {
+                file_path: self.path.to_string(),
+                checksum,
+                size_in_bytes: stamp.size_in_bytes(),
+            }));
+        }
+
         match fs::File::open(self.path) {
             Ok(f) => {
                 let reader = io::BufReader::new(f);
-                let progress = get_progress_bar("Checking dataset integrity...", None);
-                let (md5, size_in_bytes) = read_digest_and_write::<_, fs::File>(progress.wrap_read(reader), None)?;
+                let progress = get_progress_bar("Checking dataset integrity...", Some(stamp.size_in_bytes() as u64));
+                let (checksum, size_in_bytes) = read_digest_and_write::<_, fs::File>(progress.wrap_read(reader), None)?;
+                progress.finish_and_clear();
+                checksum_cache::record(self.path, stamp, checksum);
 
                 Ok(Some(JsonFile {
                     file_path: self.path.to_string(),
-                    checksum: md5,
+                    checksum,
                     size_in_bytes,
                 }))
             }
@@ -119,6 +454,13 @@ impl Dataset {
         }
     }
 
+    /// Remember the checksum of a freshly written dataset file, so that it is not rehashed on next load.
+    fn record_checksum(&self, json_file: &JsonFile) {
+        if let Ok(Some(stamp)) = FileStamp::read(self.json_path()) {
+            checksum_cache::record(self.path, stamp, json_file.checksum);
+        }
+    }
+
     fn download_file(&self) -> Result<JsonFile, DatasetError> {
         match self.source {
             DatasetSource::LocalJson => self.read_local_file(),
@@ -127,6 +469,7 @@ impl Dataset {
             DatasetSource::UrlTarArchive(ref archive, initial_path) => {
                 self.download_tar_archive(archive, initial_path.as_ref())
             }
+            DatasetSource::Generated(ref config) => self.generate(config),
         }
     }
 
@@ -145,14 +488,26 @@ impl Dataset {
         })
     }
 
-    fn download_json(&self, url: &'static str) -> Result<JsonFile, DatasetError> {
+    fn generate(&self, config: &GeneratorConfig) -> Result<JsonFile, DatasetError> {
         self.create_directories()?;
-        let mut file = fs::File::create(self.json_path()).map_err(DatasetError::FileSystemError)?;
+        let file = fs::File::create(self.json_path()).map_err(DatasetError::FileSystemError)?;
 
-        let response = make_download_request(url)?;
-        let progress = get_progress_bar("Downloading", response.content_length());
-        let (checksum, size_in_bytes) = read_digest_and_write(progress.wrap_read(response), Some(&mut file))?;
+        let progress = get_progress_bar("Generating", self.size_in_bytes.map(|s| s as u64));
+        let mut writer = DigestWriter::new(progress.wrap_write(file));
+        config.generate(&mut writer).map_err(DatasetError::InputOutputError)?;
         progress.finish_and_clear();
+        let (checksum, size_in_bytes) = writer.finish();
+
+        Ok(JsonFile {
+            file_path: self.path.to_string(),
+            checksum,
+            size_in_bytes,
+        })
+    }
+
+    fn download_json(&self, url: &'static str) -> Result<JsonFile, DatasetError> {
+        self.create_directories()?;
+        let (checksum, size_in_bytes) = fetch_file(url, self.json_path(), self.checksum)?;
 
         Ok(JsonFile {
             file_path: self.path.to_string(),
@@ -166,13 +521,7 @@ impl Dataset {
 
         self.create_directories()?;
         let archive_path = self.archive_path();
-        let mut archive_file = fs::File::create(&archive_path).map_err(DatasetError::FileSystemError)?;
-
-        let response = make_download_request(archive.url)?;
-        let progress = get_progress_bar("Downloading", response.content_length());
-        let (checksum, archive_size) = read_digest_and_write(progress.wrap_read(response), Some(&mut archive_file))?;
-        progress.finish_and_clear();
-        archive_file.flush().map_err(DatasetError::InputOutputError)?;
+        let (checksum, archive_size) = fetch_file(archive.url, &archive_path, archive.checksum)?;
 
         archive.validate_archive_checksum(checksum)?;
 
@@ -196,13 +545,7 @@ impl Dataset {
     fn download_tar_archive(&self, archive: &DatasetArchive, initial_path: &Path) -> Result<JsonFile, DatasetError> {
         self.create_directories()?;
         let archive_path = self.archive_path();
-        let mut archive_file = fs::File::create(&archive_path).map_err(DatasetError::FileSystemError)?;
-
-        let response = make_download_request(archive.url)?;
-        let progress = get_progress_bar("Downloading", response.content_length());
-        let (checksum, archive_size) = read_digest_and_write(progress.wrap_read(response), Some(&mut archive_file))?;
-        progress.finish_and_clear();
-        archive_file.flush().map_err(DatasetError::InputOutputError)?;
+        let (checksum, archive_size) = fetch_file(archive.url, &archive_path, archive.checksum)?;
 
         archive.validate_archive_checksum(checksum)?;
 
@@ -233,16 +576,125 @@ fn unpack_tar_gz(archive_path: &Path, archive_size: usize, target_path: &Path) -
     tar.unpack(target_path).map_err(DatasetError::InputOutputError)
 }
 
-fn make_download_request(url: &'static str) -> Result<reqwest::Response, DatasetError> {
+/// Get the file from `url` into `target_path`, consulting the mirror first, if configured.
+///
+/// A mirrored copy is only used if its checksum matches `expected_checksum`,
+/// otherwise the file is downloaded from `url`. The same happens if the mirror cannot be reached.
+fn fetch_file(
+    url: &str,
+    target_path: &Path,
+    expected_checksum: Sha256Digest,
+) -> Result<(Sha256Digest, usize), DatasetError> {
+    if let Some(mirror) = Mirror::from_env() {
+        match mirror.fetch_file(url, target_path)? {
+            Some((checksum, size)) if checksum == expected_checksum => return Ok((checksum, size)),
+            Some((checksum, _)) => eprintln!(
+                "Mirrored copy of {url} does not match expected checksum ({} expected, {} actual). Falling back to download.",
+                format_hex_string(&expected_checksum),
+                format_hex_string(&checksum)
+            ),
+            None => eprintln!("File {} not found in mirror {mirror}. Falling back to download.", mirrored_file_name(url)),
+        }
+    }
+
+    download_file(url, target_path)
+}
+
+fn download_file(url: &str, target_path: &Path) -> Result<(Sha256Digest, usize), DatasetError> {
+    let response = make_download_request(url)?;
+    write_response(response, target_path)
+}
+
+fn write_response(response: reqwest::Response, target_path: &Path) -> Result<(Sha256Digest, usize), DatasetError> {
+    let mut file = fs::File::create(target_path).map_err(DatasetError::FileSystemError)?;
+    let progress = get_progress_bar("Downloading", response.content_length());
+    let result = read_digest_and_write(progress.wrap_read(response), Some(&mut file))?;
+    progress.finish_and_clear();
+    file.flush().map_err(DatasetError::InputOutputError)?;
+
+    Ok(result)
+}
+
+fn make_download_request(url: &str) -> Result<reqwest::Response, DatasetError> {
     use std::time::Duration;
     let msg = format!("Downloading {url}");
     let progress = get_progress_bar(msg, None);
     progress.enable_steady_tick(Duration::from_millis(83));
-    let response = reqwest::get(url).map_err(|err| DatasetError::DownloadError(url, err))?;
+    let response = reqwest::get(url).map_err(|err| DatasetError::DownloadError(url.to_owned(), err))?;
     progress.finish();
     Ok(response)
 }
 
+/// Location consulted for dataset sources before going to their URL, set with [`MIRROR_ENV_VAR`].
+///
+/// Either a local directory or a base URL of a server. Sources are looked up by the file name of their URL,
+/// e.g. `https://zenodo.org/record/8395641/files/ast.json.gz` is looked up as `ast.json.gz`.
+enum Mirror {
+    Directory(PathBuf),
+    Url(String),
+}
+
+impl Mirror {
+    fn from_env() -> Option<Self> {
+        let value = std::env::var(MIRROR_ENV_VAR).ok().filter(|v| !v.is_empty())?;
+
+        if value.starts_with("http://") || value.starts_with("https://") {
+            Some(Self::Url(value.trim_end_matches('/').to_owned()))
+        } else {
+            Some(Self::Directory(value.into()))
+        }
+    }
+
+    fn fetch_file(&self, url: &str, target_path: &Path) -> Result<Option<(Sha256Digest, usize)>, DatasetError> {
+        let file_name = mirrored_file_name(url);
+
+        match self {
+            Self::Directory(dir) => {
+                let source = match fs::File::open(dir.join(file_name)) {
+                    Ok(f) => f,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+                    Err(err) => return Err(DatasetError::FileSystemError(err)),
+                };
+                let mut file = fs::File::create(target_path).map_err(DatasetError::FileSystemError)?;
+                let progress = get_progress_bar("Copying from mirror", source.metadata().map(|m| m.len()).ok());
+                let result = read_digest_and_write(progress.wrap_read(source), Some(&mut file))?;
+                progress.finish_and_clear();
+                file.flush().map_err(DatasetError::InputOutputError)?;
+
+                Ok(Some(result))
+            }
+            Self::Url(base) => {
+                let response = match make_download_request(&format!("{base}/{file_name}")) {
+                    Ok(response) => response,
+                    Err(err) => {
+                        eprintln!("Mirror {self} cannot be reached: {err}");
+                        return Ok(None);
+                    }
+                };
+                if response.status().is_success() {
+                    write_response(response, target_path).map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+}
+
+impl Display for Mirror {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Directory(dir) => write!(f, "{}", dir.display()),
+            Self::Url(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+fn mirrored_file_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().unwrap_or(path)
+}
+
 fn get_progress_bar<S>(msg: S, content: Option<u64>) -> indicatif::ProgressBar
 where
     S: Into<std::borrow::Cow<'static, str>>,
@@ -283,6 +735,40 @@ where
     Ok((hasher.finalize().into(), total_size))
 }
 
+/// Writer passing the data through while computing its checksum and size.
+struct DigestWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    size: usize,
+}
+
+impl<W: Write> DigestWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            size: 0,
+        }
+    }
+
+    fn finish(self) -> (Sha256Digest, usize) {
+        (self.hasher.finalize().into(), self.size)
+    }
+}
+
+impl<W: Write> Write for DigestWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.size += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 macro_rules! dataset_path {
     ($e:expr) => {
         concat! {"./data", "/", $e}
@@ -298,10 +784,18 @@ pub const fn ast() -> Dataset {
             checksum: hex!("fe8892bc52291755679267b8acf8e6665b294157cf21d8d0504c55478e2c1247"),
         }),
         checksum: hex!("c3ff840d153953ee08c1d9622b20f8c1dc367ae2abcb9c85d44100c6209571af"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[],
     }
 }
 
-pub fn crossref(size: u32) -> Dataset {
+/// One of the crossref datasets, `crossref0`, `crossref1`, `crossref2` or `crossref4`.
+pub fn crossref(size: u32) -> Result<Dataset, DatasetError> {
+    by_name(&format!("crossref{size}"))
+}
+
+fn crossref_all() -> Vec<Dataset> {
     let source = DatasetSource::UrlTarArchive(
         DatasetArchive {
             url: "https://zenodo.org/record/8395641/files/crossref.tar.gz",
@@ -310,33 +804,44 @@ pub fn crossref(size: u32) -> Dataset {
         dataset_path!(""),
     );
 
-    match size {
-        0 => Dataset {
+    vec![
+        Dataset {
             name: "crossref0",
             path: dataset_path!("crossref/crossref0.json"),
-            source,
+            source: source.clone(),
             checksum: hex!("9ef2b42a76e2d3e3785dd60f1d0c82a6986a33960d540225fcf19a4531addd0f"),
+            size_in_bytes: None,
+            format: DatasetFormat::Json,
+            tags: &[DatasetTag::Crossref],
         },
-        1 => Dataset {
+        Dataset {
             name: "crossref1",
             path: dataset_path!("crossref/crossref1.json"),
-            source,
+            source: source.clone(),
             checksum: hex!("b88ae1fd6e72c963859128c23dc7198921a7f3d422d0fe0b4ab72ae1a940f035"),
+            size_in_bytes: None,
+            format: DatasetFormat::Json,
+            tags: &[DatasetTag::Crossref],
         },
-        2 => Dataset {
+        Dataset {
             name: "crossref2",
             path: dataset_path!("crossref/crossref2.json"),
-            source,
+            source: source.clone(),
             checksum: hex!("6c452a0ee33a0fc9c98e6830e6fb411e3f4736507977c0e96ec3027488b4c95f"),
+            size_in_bytes: None,
+            format: DatasetFormat::Json,
+            tags: &[DatasetTag::Crossref],
         },
-        4 => Dataset {
+        Dataset {
             name: "crossref4",
             path: dataset_path!("crossref/crossref4.json"),
             source,
             checksum: hex!("7c5768298eb2c90ccc59b0204477f22c27d91ebcd37ea477c307600b3e0e8c29"),
+            size_in_bytes: None,
+            format: DatasetFormat::Json,
+            tags: &[DatasetTag::Crossref],
         },
-        _ => panic!("unsupported dataset crossref{size}"),
-    }
+    ]
 }
 
 pub const fn openfood() -> Dataset {
@@ -348,6 +853,9 @@ pub const fn openfood() -> Dataset {
             checksum: hex!("5e18cc0cde3c5b80cfdd6c30030e642778fb970e2e7845a573eb0663cfb6f507"),
         }),
         checksum: hex!("57ece15eecf3bbdc4d18a1215a7c3b9d0d58df0505dc4517b103dc75fac4843f"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[],
     }
 }
 
@@ -360,6 +868,9 @@ pub const fn twitter() -> Dataset {
             checksum: hex!("f391b4341c0c0c4d6483d5f6dd5c6b37c39d96abd998b4ebae0f752439921ca1"),
         }),
         checksum: hex!("f14e65d4f8df3c9144748191c1e9d46a030067af86d0cc03cc67f22149143c5d"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[],
     }
 }
 
@@ -372,6 +883,9 @@ pub const fn pison_bestbuy_large() -> Dataset {
             checksum: hex!("c8d5efe683256e1530922b7d198fd33c2c8764a594b04b6e8bd29346b09cfb3e"),
         }),
         checksum: hex!("8eee3043d6d0a11cecb43e169f70fae83c68efa7fe4a5508aa2192f717c45617"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -384,6 +898,9 @@ pub const fn pison_bestbuy_short() -> Dataset {
             checksum: hex!("6587d37e3d47e8a5bb3ac29d45121ea287b49d7eaeb8af568034c0fe0b83fa23"),
         }),
         checksum: hex!("ca0ec3d84e2212c20b50bce81e69d5cba6c3131a0fe6d47580c97a145be662b2"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -396,6 +913,9 @@ pub const fn pison_google_map_large() -> Dataset {
             checksum: hex!("bff82147ec42186a016615e888c1e009f306ab0599db20afdf102cb95e6f6e5b"),
         }),
         checksum: hex!("cdbc090edf4faeea80d917e3a2ff618fb0a42626eeac5a4521dae471e4f53574"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -408,6 +928,9 @@ pub const fn pison_google_map_short() -> Dataset {
             checksum: hex!("392d50e7eedfdf13c71e1f7a74a3bb15df85b5988ebc83fc182aec81cf3dece9"),
         }),
         checksum: hex!("8a23f138d97bbc35572ff04acacfe82877eab0c0f410741c1a9e52a0ad2a48c1"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -420,6 +943,9 @@ pub const fn pison_nspl() -> Dataset {
             checksum: hex!("3acfcfd9e846459296995bca9da4ed88c856eb8b3052f4f4eaa43c1d05e2e672"),
         }),
         checksum: hex!("174978fd3d7692dbf641c00c80b34e3ff81f0d3d4602c89ee231b989e6a30dd3"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -432,6 +958,9 @@ pub const fn pison_twitter_large() -> Dataset {
             checksum: hex!("4e8bfb5e68bd1b4a9c69c7f2515eb65608ce84e3c284ecb1fe6908eb57b4e650"),
         }),
         checksum: hex!("2357e2bdba1d621a20c2278a88bdec592e93c680de17d8403d9e3018c7539da6"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -444,6 +973,9 @@ pub const fn pison_twitter_short() -> Dataset {
             checksum: hex!("7d6cde2fe297783338cc507ad8046c3e8e0a905e809bde6af64b73f9bb75afe8"),
         }),
         checksum: hex!("177b1764cade21af7b4962f23836431dab9c0beb320bdbff11bb6c8006f360cb"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -456,6 +988,9 @@ pub const fn pison_walmart_large() -> Dataset {
             checksum: hex!("3ba4309dd620463045a3996596805f738ead2b257cf7152ea6b1f8ab339e71f4"),
         }),
         checksum: hex!("ebad2cf96871a1c2277c2a19dcc5818f9c2aed063bc8a56459f378024c5a6e14"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -468,6 +1003,9 @@ pub const fn pison_walmart_short() -> Dataset {
             checksum: hex!("e0c4163bfb1da0cbcaf2cc0d26318b0d380bd5defb64113510ea7319d64a252b"),
         }),
         checksum: hex!("acf0afde1e83cd0a2dde829b63846acb6cf98fc7c9d210f3c230c285b903aff6"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -480,6 +1018,9 @@ pub const fn pison_wiki() -> Dataset {
             checksum: hex!("60755f971307f29cebbb7daa8624acec41c257dfef5c1543ca0934f5b07edcf7"),
         }),
         checksum: hex!("1abea7979812edc38651a631b11faf64f1eb5a61e2ee875b4e4d4f7b15a8cea9"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Pison],
     }
 }
 
@@ -488,7 +1029,10 @@ pub const fn nativejson_canada() -> Dataset {
         name: "nativejson_canada",
         path: dataset_path!("nativejson/canada.json"),
         source: DatasetSource::UrlJson("https://raw.githubusercontent.com/miloyip/nativejson-benchmark/478d5727c2a4048e835a29c65adecc7d795360d5/data/canada.json"),
-        checksum: hex!("f83b3b354030d5dd58740c68ac4fecef64cb730a0d12a90362a7f23077f50d78")
+        checksum: hex!("f83b3b354030d5dd58740c68ac4fecef64cb730a0d12a90362a7f23077f50d78"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::NativeJson],
     }
 }
 
@@ -497,7 +1041,10 @@ pub const fn nativejson_citm() -> Dataset {
         name: "nativejson_citm",
         path: dataset_path!("nativejson/citm.json"),
         source: DatasetSource::UrlJson("https://raw.githubusercontent.com/miloyip/nativejson-benchmark/478d5727c2a4048e835a29c65adecc7d795360d5/data/citm_catalog.json"),
-        checksum: hex!("a73e7a883f6ea8de113dff59702975e60119b4b58d451d518a929f31c92e2059")
+        checksum: hex!("a73e7a883f6ea8de113dff59702975e60119b4b58d451d518a929f31c92e2059"),
+        size_in_bytes: None,
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::NativeJson],
     }
 }
 
@@ -507,6 +1054,221 @@ pub const fn az_tenants() -> Dataset {
         path: dataset_path!("small/az_tenants.json"),
         source: DatasetSource::LocalJson,
         checksum: hex!("f4aa54189ddb9fff22a20bf24cb8bb2656880abdb0a01cf1a48cd3ddd30a87d0"),
+        size_in_bytes: Some(37_743),
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Small],
+    }
+}
+
+/// Records nested over two thousand levels deep, past the recursion limit of DOM parsers like `serde_json`.
+pub const fn generated_deep() -> Dataset {
+    Dataset {
+        name: "generated_deep",
+        path: dataset_path!("generated/deep.json"),
+        source: DatasetSource::Generated(GeneratorConfig::new(1).records(64).depth(2048).fan_out(1)),
+        checksum: hex!("f9b54f1954f4d6e50a947cb972c559acc574521c37a371770fa64e20eae97986"),
+        size_in_bytes: Some(616_363),
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Generated],
+    }
+}
+
+/// Very large objects and arrays with keys drawn from a big, skewed pool.
+pub const fn generated_wide() -> Dataset {
+    Dataset {
+        name: "generated_wide",
+        path: dataset_path!("generated/wide.json"),
+        source: DatasetSource::Generated(
+            GeneratorConfig::new(2)
+                .records(4)
+                .depth(2)
+                .fan_out(512)
+                .keys(1024, KeyDistribution::Skewed),
+        ),
+        checksum: hex!("6a225024c2e8a772f1d8511531be14e540cef3bb31a4215953f9e71b88dbec34"),
+        size_in_bytes: Some(14_489_054),
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Generated],
+    }
+}
+
+/// Long strings where one in ten characters is an escape sequence.
+pub const fn generated_escapes() -> Dataset {
+    Dataset {
+        name: "generated_escapes",
+        path: dataset_path!("generated/escapes.json"),
+        source: DatasetSource::Generated(
+            GeneratorConfig::new(3)
+                .records(512)
+                .depth(3)
+                .fan_out(8)
+                .string_length(16, 64)
+                .escape_density(0.1),
+        ),
+        checksum: hex!("c80fe56509313814fae6d86002a586fe4ae2c2e1afb391ae24073be8837ca5e8"),
+        size_in_bytes: Some(7_559_923),
+        format: DatasetFormat::Json,
+        tags: &[DatasetTag::Generated],
+    }
+}
+
+/// Many small records in JSON Lines, similar to a stream of structured logs.
+pub const fn generated_logs() -> Dataset {
+    Dataset {
+        name: "generated_logs",
+        path: dataset_path!("generated/logs.jsonl"),
+        source: DatasetSource::Generated(
+            GeneratorConfig::new(5)
+                .records(32768)
+                .depth(2)
+                .fan_out(8)
+                .keys(32, KeyDistribution::Skewed)
+                .string_length(8, 48)
+                .array_ratio(0.25)
+                .json_lines(),
+        ),
+        checksum: hex!("8fb4648c59a96b9e2fd6c4aa12cbacfa55934a83a6069cc83dff38c91bb40434"),
+        size_in_bytes: Some(47_847_943),
+        format: DatasetFormat::JsonLines,
+        tags: &[DatasetTag::Generated],
+    }
+}
+
+/// A generated dataset with an arbitrary shape, e.g. one point of a scaling curve.
+///
+/// The expected checksum is computed by running the generator, so this is as expensive as generating the file.
+/// The name and path are leaked only once per distinct name.
+pub fn generated(name: &str, config: GeneratorConfig) -> Dataset {
+    let mut writer = DigestWriter::new(io::sink());
+    config.generate(&mut writer).expect("writing to a sink does not fail");
+    let (checksum, size_in_bytes) = writer.finish();
+    let name = intern(name.to_owned());
+    let extension = match config.format() {
+        DatasetFormat::Json => "json",
+        DatasetFormat::JsonLines => "jsonl",
+    };
+    let path = intern(format!(dataset_path!("generated/{}.{}"), name, extension));
+
+    Dataset {
+        name,
+        path,
+        source: DatasetSource::Generated(config),
+        checksum,
+        size_in_bytes: Some(size_in_bytes),
+        format: config.format(),
+        tags: &[DatasetTag::Generated],
+    }
+}
+
+lazy_static! {
+    static ref INTERNED: Mutex<HashSet<&'static str>> = Mutex::new(HashSet::new());
+}
+
+/// Leak the string unless an equal one was leaked before, so that datasets created repeatedly,
+/// e.g. one per bench of a family, do not leak their names on every call.
+fn intern(string: String) -> &'static str {
+    let mut interned = INTERNED.lock().unwrap();
+    if let Some(&existing) = interned.get(string.as_str()) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(string.into_boxed_str());
+    interned.insert(leaked);
+
+    leaked
+}
+
+pub fn crossref_family() -> DatasetFamily {
+    DatasetFamily::new("crossref", crossref_all())
+}
+
+/// Generated documents of the same shape with a growing number of records, from about 350KB to 90MB.
+pub fn generated_size_family() -> DatasetFamily {
+    let shape = GeneratorConfig::new(4)
+        .depth(4)
+        .fan_out(6)
+        .keys(64, KeyDistribution::Skewed)
+        .string_length(4, 32);
+    let members = [16, 64, 256, 1024, 4096]
+        .into_iter()
+        .map(|records| generated(&format!("generated_size{records}"), shape.records(records)))
+        .collect();
+
+    DatasetFamily::new("generated_size", members)
+}
+
+pub fn all() -> Vec<Dataset> {
+    let mut datasets = vec![ast()];
+    datasets.extend(crossref_all());
+    datasets.extend([
+        openfood(),
+        twitter(),
+        pison_bestbuy_large(),
+        pison_bestbuy_short(),
+        pison_google_map_large(),
+        pison_google_map_short(),
+        pison_nspl(),
+        pison_twitter_large(),
+        pison_twitter_short(),
+        pison_walmart_large(),
+        pison_walmart_short(),
+        pison_wiki(),
+        nativejson_canada(),
+        nativejson_citm(),
+        az_tenants(),
+        generated_deep(),
+        generated_wide(),
+        generated_escapes(),
+        generated_logs(),
+    ]);
+
+    datasets
+}
+
+pub fn by_name(name: &str) -> Result<Dataset, DatasetError> {
+    all()
+        .into_iter()
+        .find(|d| d.name == name)
+        .ok_or_else(|| DatasetError::UnknownDataset(name.to_owned()))
+}
+
+pub fn with_tag(tag: DatasetTag) -> Vec<Dataset> {
+    all().into_iter().filter(|d| d.has_tag(tag)).collect()
+}
+
+impl DatasetTag {
+    pub const ALL: [DatasetTag; 5] = [
+        Self::Pison,
+        Self::NativeJson,
+        Self::Crossref,
+        Self::Small,
+        Self::Generated,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pison => "pison",
+            Self::NativeJson => "nativejson",
+            Self::Crossref => "crossref",
+            Self::Small => "small",
+            Self::Generated => "generated",
+        }
+    }
+}
+
+impl Display for DatasetTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for DatasetTag {
+    type Err = DatasetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.name() == s)
+            .ok_or_else(|| DatasetError::UnknownTag(s.to_owned()))
     }
 }
 
@@ -516,10 +1278,18 @@ pub enum DatasetError {
     FileSystemError(#[source] std::io::Error),
     #[error("I/O error reading dataset contents: {0}")]
     InputOutputError(#[source] std::io::Error),
+    #[error("Unknown dataset: {0}")]
+    UnknownDataset(String),
+    #[error("Unknown dataset tag: {0}")]
+    UnknownTag(String),
+    #[error("Unknown compression format: {0}, expected gzip or zstd")]
+    UnknownCompression(String),
+    #[error("Compression format {0} is not supported, it might require enabling a feature")]
+    UnsupportedCompression(Compression),
     #[error("Invalid dataset path: {0} is not a valid path")]
     InvalidPath(&'static str),
     #[error("Error downloading a dataset from {0}: {1}")]
-    DownloadError(&'static str, #[source] ::reqwest::Error),
+    DownloadError(String, #[source] ::reqwest::Error),
     #[error(
         "Checksum validation failed. \
     The URL source might be corrupted. \
@@ -532,6 +1302,12 @@ pub enum DatasetError {
     Expected archive from {0} to have SHA2 checksum of {}, but it has {}.", format_hex_string(.1), format_hex_string(.2)
     )]
     InvalidArchiveChecksum(&'static str, Sha256Digest, Sha256Digest),
+    #[error(
+        "Checksum validation failed. \
+    The generator output has changed. \
+    Expected generated dataset {0} to have SHA2 checksum of {}, but it has {}.", format_hex_string(.1), format_hex_string(.2)
+    )]
+    InvalidGeneratedChecksum(&'static str, Sha256Digest, Sha256Digest),
 }
 
 fn format_hex_string(bytes: &[u8]) -> impl Display {
@@ -541,3 +1317,44 @@ fn format_hex_string(bytes: &[u8]) -> impl Display {
         f
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_dataset_is_valid() {
+        let status = az_tenants().verify().unwrap();
+
+        assert_eq!(status, DatasetStatus::Valid { size_in_bytes: 37_743 });
+    }
+
+    #[test]
+    fn generated_names_are_leaked_once() {
+        let config = GeneratorConfig::new(1).records(1).depth(1).fan_out(1);
+
+        let first = generated("interned", config);
+        let second = generated("interned", config);
+
+        assert!(std::ptr::eq(first.name(), second.name()));
+        assert!(std::ptr::eq(first.path(), second.path()));
+    }
+
+    #[test]
+    fn size_mismatch_is_reported() {
+        let dataset = Dataset {
+            size_in_bytes: Some(1),
+            ..az_tenants()
+        };
+
+        let status = dataset.verify().unwrap();
+
+        assert_eq!(
+            status,
+            DatasetStatus::WrongSize {
+                expected_size_in_bytes: 1,
+                actual_size_in_bytes: 37_743
+            }
+        );
+    }
+}
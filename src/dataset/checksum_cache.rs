@@ -0,0 +1,114 @@
+//! Cache of dataset checksums, so that large files are not rehashed on every run.
+//!
+//! Entries are keyed by path and invalidated when the size or modification time of the file changes.
+//! The cache is kept in memory for the duration of the process and persisted to [`CACHE_PATH`].
+use super::Sha256Digest;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, io, path::Path, sync::Mutex, time::UNIX_EPOCH};
+
+/// Setting this to a non-empty value other than `0` disables the cache and forces a full rehash of every dataset.
+pub const STRICT_CHECKSUMS_ENV_VAR: &str = "RSONPATH_BENCH_STRICT_CHECKSUMS";
+
+const CACHE_PATH: &str = "./data/.checksums.json";
+
+lazy_static! {
+    static ref MEMO: Mutex<Option<HashMap<String, CacheEntry>>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct FileStamp {
+    size_in_bytes: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    #[serde(flatten)]
+    stamp: FileStamp,
+    checksum: String,
+}
+
+impl FileStamp {
+    /// Read the size and modification time of the file, or `None` if it does not exist.
+    pub(super) fn read(path: &Path) -> io::Result<Option<Self>> {
+        let metadata = match fs::metadata(path) {
+            Ok(m) => m,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let modified = metadata.modified()?.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        Ok(Some(Self {
+            size_in_bytes: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        }))
+    }
+
+    pub(super) fn size_in_bytes(&self) -> usize {
+        self.size_in_bytes as usize
+    }
+}
+
+pub fn is_strict() -> bool {
+    std::env::var(STRICT_CHECKSUMS_ENV_VAR).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Get the cached checksum of the file at `path`, if it was computed for the file in its current state.
+pub(super) fn lookup(path: &str, stamp: &FileStamp) -> Option<Sha256Digest> {
+    if is_strict() {
+        return None;
+    }
+
+    let mut memo = MEMO.lock().unwrap();
+    let entries = memo.get_or_insert_with(load);
+    let entry = entries.get(path).filter(|e| e.stamp == *stamp)?;
+
+    parse_hex(&entry.checksum)
+}
+
+/// Store the checksum of the file at `path` in memory and on disk.
+///
+/// Failing to persist the cache is not an error, the checksum will simply be recomputed on the next run.
+pub(super) fn record(path: &str, stamp: FileStamp, checksum: Sha256Digest) {
+    let mut memo = MEMO.lock().unwrap();
+    let entries = memo.get_or_insert_with(load);
+    entries.insert(
+        path.to_owned(),
+        CacheEntry {
+            stamp,
+            checksum: super::format_hex_string(&checksum).to_string(),
+        },
+    );
+
+    save(entries).unwrap_or(());
+}
+
+fn load() -> HashMap<String, CacheEntry> {
+    fs::read(CACHE_PATH)
+        .ok()
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save(entries: &HashMap<String, CacheEntry>) -> io::Result<()> {
+    let contents = serde_json::to_vec_pretty(entries)?;
+    let tmp_path = Path::new(CACHE_PATH).with_extension("json.tmp");
+    fs::create_dir_all(Path::new(CACHE_PATH).parent().expect("cache path has a parent"))?;
+    fs::write(&tmp_path, contents)?;
+    fs::rename(tmp_path, CACHE_PATH)
+}
+
+fn parse_hex(hex: &str) -> Option<Sha256Digest> {
+    let mut digest = [0; 32];
+    if hex.len() != 2 * digest.len() {
+        return None;
+    }
+    for (i, b) in digest.iter_mut().enumerate() {
+        *b = u8::from_str_radix(hex.get(2 * i..2 * i + 2)?, 16).ok()?;
+    }
+
+    Some(digest)
+}
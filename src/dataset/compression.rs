@@ -0,0 +1,101 @@
+//! Compressed copies of dataset files, read back through streaming decoders.
+//!
+//! Gzip is always supported, zstd requires the `zstd` feature.
+use serde::Deserialize;
+use std::{
+    fmt::Display,
+    io::{self, Read, Write},
+    str::FromStr,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub const ALL: [Compression; 2] = [Self::Gzip, Self::Zstd];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Zstd => "zst",
+        }
+    }
+
+    /// Path of the compressed copy of the JSON file at `json_path`, e.g. `./data/twitter/twitter.json.gz`.
+    pub fn compressed_path(&self, json_path: &str) -> String {
+        format!("{json_path}.{}", self.extension())
+    }
+
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Self::Gzip => true,
+            Self::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+
+    /// Wrap `reader` in a streaming decoder producing the decompressed contents.
+    pub fn decoder<'a, R: Read + 'a>(&self, reader: R) -> io::Result<Box<dyn Read + 'a>> {
+        match self {
+            Self::Gzip => Ok(Box::new(flate2::read::MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Ok(Box::new(zstd::stream::read::Decoder::new(reader)?)),
+            #[cfg(not(feature = "zstd"))]
+            Self::Zstd => Err(unsupported()),
+        }
+    }
+
+    /// Compress everything from `reader` into `writer`, returning the writer once the stream is finished.
+    pub fn compress<R: Read, W: Write>(&self, mut reader: R, writer: W) -> io::Result<W> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+                io::copy(&mut reader, &mut encoder)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(writer, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+                io::copy(&mut reader, &mut encoder)?;
+                encoder.finish()
+            }
+            #[cfg(not(feature = "zstd"))]
+            Self::Zstd => Err(unsupported()),
+        }
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "zstd compression requires the `zstd` feature",
+    )
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Compression {
+    type Err = super::DatasetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.name() == s)
+            .ok_or_else(|| super::DatasetError::UnknownCompression(s.to_owned()))
+    }
+}
@@ -0,0 +1,332 @@
+//! Deterministic synthetic JSON documents with a controllable shape.
+//!
+//! A generated document is a top-level array of records, or a JSON Lines file with one record per line,
+//! each a tree of nested arrays and objects with scalar leaves. Generation uses its own PRNG and integer-only
+//! arithmetic, so the same config produces byte-identical output on every platform and the checksum
+//! of a generated dataset is stable.
+use super::DatasetFormat;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneratorConfig {
+    seed: u64,
+    records: usize,
+    depth: usize,
+    fan_out: usize,
+    key_pool: usize,
+    key_distribution: KeyDistribution,
+    min_string_length: usize,
+    max_string_length: usize,
+    escape_density: f64,
+    array_ratio: f64,
+    json_lines: bool,
+}
+
+/// How keys of an object are picked from the pool of key names `k0`, `k1`, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyDistribution {
+    /// Every key in the pool is equally likely.
+    Uniform,
+    /// Keys earlier in the pool are much more likely, similarly to real-world schemas
+    /// with a few common keys and a long tail of rare ones.
+    Skewed,
+}
+
+impl GeneratorConfig {
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            records: 1,
+            depth: 4,
+            fan_out: 4,
+            key_pool: 16,
+            key_distribution: KeyDistribution::Uniform,
+            min_string_length: 0,
+            max_string_length: 16,
+            escape_density: 0.0,
+            array_ratio: 0.5,
+            json_lines: false,
+        }
+    }
+
+    /// Number of records in the top-level array.
+    pub const fn records(self, records: usize) -> Self {
+        Self { records, ..self }
+    }
+
+    /// Nesting depth of every record. Depth of zero makes every record a single scalar.
+    pub const fn depth(self, depth: usize) -> Self {
+        Self { depth, ..self }
+    }
+
+    /// Number of children of every array and object.
+    pub const fn fan_out(self, fan_out: usize) -> Self {
+        Self { fan_out, ..self }
+    }
+
+    /// Number of distinct key names and how keys are picked from them.
+    pub const fn keys(self, key_pool: usize, key_distribution: KeyDistribution) -> Self {
+        Self {
+            key_pool,
+            key_distribution,
+            ..self
+        }
+    }
+
+    /// Inclusive range of lengths of string values, counted in characters.
+    pub const fn string_length(self, min_string_length: usize, max_string_length: usize) -> Self {
+        Self {
+            min_string_length,
+            max_string_length,
+            ..self
+        }
+    }
+
+    /// Probability that a character of a string value is an escape sequence.
+    pub const fn escape_density(self, escape_density: f64) -> Self {
+        Self { escape_density, ..self }
+    }
+
+    /// Probability that a container is an array rather than an object.
+    pub const fn array_ratio(self, array_ratio: f64) -> Self {
+        Self { array_ratio, ..self }
+    }
+
+    /// Write every record on its own line instead of wrapping them in an array.
+    pub const fn json_lines(self) -> Self {
+        Self {
+            json_lines: true,
+            ..self
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn format(&self) -> DatasetFormat {
+        if self.json_lines {
+            DatasetFormat::JsonLines
+        } else {
+            DatasetFormat::Json
+        }
+    }
+
+    pub fn generate<W: Write>(&self, writer: W) -> io::Result<()> {
+        Generator::new(self, writer).write_document()
+    }
+}
+
+const ESCAPES: [&str; 6] = [r#"\""#, r"\\", r"\/", r"\n", r"\t", r"\u00e9"];
+const CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 ";
+
+struct Generator<'a, W: Write> {
+    config: &'a GeneratorConfig,
+    rng: SplitMix64,
+    writer: io::BufWriter<W>,
+    escape_threshold: u64,
+    array_threshold: u64,
+    stack: Vec<Frame>,
+}
+
+struct Frame {
+    is_array: bool,
+    depth: usize,
+    written: usize,
+    used_keys: Vec<usize>,
+}
+
+impl<'a, W: Write> Generator<'a, W> {
+    fn new(config: &'a GeneratorConfig, writer: W) -> Self {
+        Self {
+            config,
+            rng: SplitMix64(config.seed),
+            writer: io::BufWriter::new(writer),
+            escape_threshold: threshold(config.escape_density),
+            array_threshold: threshold(config.array_ratio),
+            stack: vec![],
+        }
+    }
+
+    fn write_document(mut self) -> io::Result<()> {
+        if self.config.json_lines {
+            for _ in 0..self.config.records {
+                self.write_record()?;
+                self.writer.write_all(b"\n")?;
+            }
+
+            return self.writer.flush();
+        }
+
+        self.writer.write_all(b"[")?;
+        for i in 0..self.config.records {
+            if i > 0 {
+                self.writer.write_all(b",\n")?;
+            }
+            self.write_record()?;
+        }
+        self.writer.write_all(b"]\n")?;
+
+        self.writer.flush()
+    }
+
+    /// Write a single record without recursion, so that arbitrarily deep documents can be generated.
+    fn write_record(&mut self) -> io::Result<()> {
+        self.start_value(self.config.depth)?;
+
+        while let Some(frame) = self.stack.last_mut() {
+            if frame.written == self.config.fan_out {
+                let close = if frame.is_array { b"]" } else { b"}" };
+                self.stack.pop();
+                self.writer.write_all(close)?;
+                continue;
+            }
+
+            let (is_array, child_depth, position) = (frame.is_array, frame.depth - 1, frame.written);
+            frame.written += 1;
+            if position > 0 {
+                self.writer.write_all(b",")?;
+            }
+            if !is_array {
+                self.write_key(position)?;
+            }
+            self.start_value(child_depth)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a scalar at depth zero, or open a container and push its frame to be filled.
+    fn start_value(&mut self, depth: usize) -> io::Result<()> {
+        if depth == 0 {
+            return self.write_scalar();
+        }
+
+        let is_array = self.rng.next_u64() >> 32 < self.array_threshold;
+        self.writer.write_all(if is_array { b"[" } else { b"{" })?;
+        self.stack.push(Frame {
+            is_array,
+            depth,
+            written: 0,
+            used_keys: vec![],
+        });
+
+        Ok(())
+    }
+
+    /// Keys are unique within an object: a key drawn again gets its position appended.
+    fn write_key(&mut self, position: usize) -> io::Result<()> {
+        let pool = self.config.key_pool.max(1);
+        let index = match self.config.key_distribution {
+            KeyDistribution::Uniform => self.rng.below(pool),
+            KeyDistribution::Skewed => {
+                let bound = self.rng.below(pool) + 1;
+                self.rng.below(bound)
+            }
+        };
+        let frame = self.stack.last_mut().expect("keys are written inside an object");
+
+        if frame.used_keys.contains(&index) {
+            write!(self.writer, "\"k{index}_{position}\":")
+        } else {
+            frame.used_keys.push(index);
+            write!(self.writer, "\"k{index}\":")
+        }
+    }
+
+    fn write_scalar(&mut self) -> io::Result<()> {
+        match self.rng.below(10) {
+            0..=3 => self.write_string(),
+            4..=6 => write!(self.writer, "{}", self.rng.next_u64() as i32),
+            7 => write!(self.writer, "{}.{:03}", self.rng.below(100_000), self.rng.below(1000)),
+            8 => write!(self.writer, "{}", self.rng.below(2) == 0),
+            _ => self.writer.write_all(b"null"),
+        }
+    }
+
+    fn write_string(&mut self) -> io::Result<()> {
+        let min = self.config.min_string_length.min(self.config.max_string_length);
+        let max = self.config.min_string_length.max(self.config.max_string_length);
+        let length = min + self.rng.below(max - min + 1);
+
+        self.writer.write_all(b"\"")?;
+        for _ in 0..length {
+            if self.rng.next_u64() >> 32 < self.escape_threshold {
+                let escape = ESCAPES[self.rng.below(ESCAPES.len())];
+                self.writer.write_all(escape.as_bytes())?;
+            } else {
+                let character = CHARACTERS[self.rng.below(CHARACTERS.len())];
+                self.writer.write_all(&[character])?;
+            }
+        }
+        self.writer.write_all(b"\"")
+    }
+}
+
+/// Convert a probability into a threshold for the upper 32 bits of a random number.
+/// This is the only floating-point operation, and it is exact for all inputs.
+fn threshold(probability: f64) -> u64 {
+    (probability.clamp(0.0, 1.0) * (1_u64 << 32) as f64) as u64
+}
+
+/// The SplitMix64 generator. Fast, good enough for shaping data, and fully specified,
+/// unlike generators from external crates, whose output may change between versions.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform-enough number in `0..bound`. `bound` must be positive.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use sha2::{Digest, Sha256};
+
+    fn generate(config: GeneratorConfig) -> Vec<u8> {
+        let mut output = vec![];
+        config.generate(&mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn small_config_has_a_stable_digest() {
+        let config = GeneratorConfig::new(42)
+            .records(8)
+            .depth(3)
+            .fan_out(3)
+            .keys(16, KeyDistribution::Skewed)
+            .string_length(2, 8)
+            .escape_density(0.2);
+        let output = generate(config);
+
+        serde_json::from_slice::<serde_json::Value>(&output).unwrap();
+        assert_eq!(output, generate(config));
+        assert_eq!(
+            <[u8; 32]>::from(Sha256::digest(&output)),
+            hex!("88777e2f5e72e8912190e8b3113a75c59a9f09fc9ecdfa59d85c6c525856b20e")
+        );
+    }
+
+    #[test]
+    fn json_lines_have_one_record_per_line() {
+        let output = generate(GeneratorConfig::new(7).records(5).depth(2).fan_out(2).json_lines());
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(output.lines().count(), 5);
+        for line in output.lines() {
+            serde_json::from_str::<serde_json::Value>(line).unwrap();
+        }
+    }
+}
@@ -1,44 +1,137 @@
-use self::implementation::prepare;
-use self::{benchmark_options::BenchmarkOptions, implementation::prepare_with_id};
+use self::{
+    benchmark_options::BenchmarkOptions,
+    implementation::{load_records, prepare_records_with_id, prepare_with_id, LoadRecord, PreparedRecords},
+    memory::{MemoryUsage, Phase},
+    perf::{PerfCounter, PerfMeasurement},
+    report::{DatasetInfo, ReportError, TargetResult},
+    simd::SimdConfig,
+    suite::SuiteError,
+    verification::{ResultMismatchDetails, ResultSummary},
+};
+#[cfg(feature = "jsonpath_lib")]
+use crate::implementations::jsonpath_lib::{JsonpathLib, JsonpathLibError};
+#[cfg(feature = "jsonski")]
+use crate::implementations::jsonski::{JsonSki, JsonSkiError};
+#[cfg(feature = "jsurfer")]
+use crate::implementations::rust_jsurfer::{JSurfer, JSurferError};
 use crate::{
-    dataset,
+    dataset::{self, Compression, DatasetFormat},
     implementations::{
+        external::{ExternalCommand, ExternalEngine, ExternalEngineError},
+        jaq::{Jaq, JaqError},
         jsonpath_rust::{JsonpathRust, JsonpathRustError},
-        rsonpath::{Rsonpath, RsonpathCount, RsonpathError, RsonpathMmap, RsonpathMmapCount},
-        rust_jsurfer::{JSurfer, JSurferError},
+        rsonpath::{
+            MatchCollection, Rsonpath, RsonpathApproxSpans, RsonpathBuffered, RsonpathBufferedApproxSpans,
+            RsonpathBufferedCount, RsonpathBufferedIndices, RsonpathCompressed, RsonpathCompressedCount, RsonpathCount,
+            RsonpathError, RsonpathIndices, RsonpathMmap, RsonpathMmapApproxSpans, RsonpathMmapCount,
+            RsonpathMmapIndices,
+        },
+        serde_json_baseline::{SerdeJsonError, SerdeJsonIgnoredAny, SerdeJsonValue},
         serde_json_path::{SerdeJsonPath, SerdeJsonPathError},
     },
 };
-use criterion::{Criterion, Throughput};
+use criterion::{
+    measurement::{Measurement, WallTime},
+    Bencher, BenchmarkGroup, BenchmarkId, Criterion, Throughput,
+};
 use implementation::{Implementation, PreparedQuery};
-use std::{path::PathBuf, time::Duration};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
 use thiserror::Error;
 
 pub mod benchmark_options;
+pub mod comparison;
+pub mod family;
 pub mod implementation;
+pub mod memory;
+pub mod perf;
+pub mod report;
+pub mod simd;
+pub mod suite;
+pub mod verification;
 
 #[derive(Clone, Copy, Debug)]
 pub enum BenchTarget<'q> {
     RsonpathMmap(&'q str, ResultType),
     Rsonpath(&'q str, ResultType),
+    RsonpathBuffered(&'q str, ResultType),
+    /// Full rsonpath targets with an explicit choice of what happens to each match.
+    RsonpathWithSink(&'q str, MatchCollection),
+    RsonpathMmapWithSink(&'q str, MatchCollection),
+    RsonpathBufferedWithSink(&'q str, MatchCollection),
+    /// rsonpath reading a compressed copy of the dataset through a streaming decoder.
+    /// Only the `Full` and `Count` result types are supported.
+    RsonpathCompressed(&'q str, Compression, ResultType),
+    /// JSurfer via JNI. Requires the `jsurfer` feature.
+    #[cfg(feature = "jsurfer")]
     JSurfer(&'q str),
     JsonpathRust(&'q str),
     SerdeJsonPath(&'q str),
+    /// jsonpath_lib. Requires the `jsonpath_lib` feature, which enables `preserve_order` in `serde_json`.
+    #[cfg(feature = "jsonpath_lib")]
+    JsonpathLib(&'q str),
+    /// JSONSki through FFI. Requires the `jsonski` feature.
+    #[cfg(feature = "jsonski")]
+    JsonSki(&'q str),
+    /// jaq running the query translated into a jq filter. Only simple queries are supported.
+    Jaq(&'q str),
+    /// Engine running in a separate process, see [`external`](crate::implementations::external).
+    /// The command's name is the default id of the target.
+    External(&'q str, &'q ExternalCommand),
+    /// Baseline parsing the document into a `serde_json::Value` and traversing it for simple queries.
+    SerdeJsonValue(&'q str),
+    /// Baseline only parsing the document with `serde_json`, without evaluating the query.
+    SerdeJsonIgnoredAny(&'q str),
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BenchPhase {
+    Load,
+    Compile,
+    Run,
+}
+
+impl BenchPhase {
+    pub fn target_id(&self, target: &str) -> String {
+        let suffix = match self {
+            Self::Load => "load",
+            Self::Compile => "compile",
+            Self::Run => "run",
+        };
+        format!("{target}_{suffix}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ResultType {
     Full,
     Count,
+    Indices,
+    ApproxSpans,
 }
 
 pub struct Benchset {
     id: String,
     options: BenchmarkOptions,
+    dataset: dataset::Dataset,
     json_document: dataset::JsonFile,
     implementations: Vec<Box<dyn BenchFn>>,
     measure_file_load: bool,
     measure_compilation_time: bool,
+    measure_phases: bool,
+    perf_counters: Vec<PerfCounter>,
+    simd_configs: Vec<SimdConfig>,
+    verify_results: bool,
+    /// Set for members of a [`family::BenchsetFamily`], whose targets are parameterized by the input size.
+    in_family: bool,
+    /// Number of records in the dataset, set if the query is applied to each record separately.
+    records: Option<usize>,
 }
 
 pub struct ConfiguredBenchset {
@@ -46,21 +139,233 @@ pub struct ConfiguredBenchset {
 }
 
 impl ConfiguredBenchset {
-    pub fn run(&self, c: &mut Criterion) {
+    pub fn run(&self, c: &mut Criterion) -> Result<(), BenchmarkError> {
         let bench = &self.source;
-        let mut group = c.benchmark_group(&bench.id);
+        let started_at = SystemTime::now();
+        let mut group = self.benchmark_group(c, &bench.id);
+
+        for implementation in bench.implementations.iter() {
+            let id = implementation.id();
+            simd::with_forced(implementation.simd(), || {
+                group.bench_function(id, |b| implementation.bench(b));
+            });
+
+            if bench.measure_phases {
+                implementation.bench_phases(&mut group);
+            }
+        }
+
+        group.finish();
+        self.print_record_throughput(started_at)?;
+
+        let counters = self.run_perf_counters(c);
+
+        let memory = if memory::is_enabled() {
+            self.measure_memory()?
+        } else {
+            vec![]
+        };
+
+        if report::report_path().is_some() {
+            self.collect_results(started_at, &memory, &counters)?;
+        }
+
+        Ok(())
+    }
+
+    /// Run the targets inside the group of a family, with the size of the input as the benchmark parameter.
+    ///
+    /// Phases and perf counters are not measured for family members.
+    fn run_as_family_member(
+        &self,
+        group: &mut BenchmarkGroup<'_, WallTime>,
+        started_at: SystemTime,
+    ) -> Result<(), BenchmarkError> {
+        let bench = &self.source;
+        let size_in_bytes = bench.json_document.size_in_bytes;
+
+        bench.options.apply_to(group);
+        group.throughput(Throughput::BytesDecimal(u64::try_from(size_in_bytes).unwrap()));
+        for implementation in bench.implementations.iter() {
+            let id = BenchmarkId::new(implementation.id(), size_in_bytes);
+            simd::with_forced(implementation.simd(), || {
+                group.bench_function(id, |b| implementation.bench(b));
+            });
+        }
+        self.print_record_throughput(started_at)?;
+
+        let memory = if memory::is_enabled() {
+            self.measure_memory()?
+        } else {
+            vec![]
+        };
+
+        if report::report_path().is_some() {
+            self.collect_results(started_at, &memory, &[])?;
+        }
+
+        Ok(())
+    }
+
+    /// Criterion reports throughput only in bytes, so throughput in records is computed from its estimates.
+    fn print_record_throughput(&self, started_at: SystemTime) -> Result<(), BenchmarkError> {
+        let bench = &self.source;
+        let Some(records) = bench.records else {
+            return Ok(());
+        };
+        let parameter = bench.in_family.then(|| bench.json_document.size_in_bytes.to_string());
+
+        for implementation in bench.implementations.iter() {
+            let estimates =
+                report::read_criterion_estimates(&bench.id, implementation.id(), parameter.as_deref(), started_at)?;
+            if let Some((mean, _)) = estimates {
+                let per_second = records as f64 * 1_000_000_000.0 / mean.point_estimate;
+                let target = match &parameter {
+                    Some(parameter) => format!("{}/{parameter}", implementation.id()),
+                    None => implementation.id().to_owned(),
+                };
+                println!("{}/{target} throughput: {per_second:.0} records/s", bench.id);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn benchmark_group<'a, M: Measurement>(&self, c: &'a mut Criterion<M>, group_id: &str) -> BenchmarkGroup<'a, M> {
+        let bench = &self.source;
+        let mut group = c.benchmark_group(group_id);
 
         bench.options.apply_to(&mut group);
         group.throughput(Throughput::BytesDecimal(
             u64::try_from(bench.json_document.size_in_bytes).unwrap(),
         ));
 
+        group
+    }
+
+    /// Run all targets again for every requested counter that is available, each in its own group.
+    /// Returns the counters that were actually measured.
+    fn run_perf_counters(&self, c: &mut Criterion) -> Vec<PerfCounter> {
+        let bench = &self.source;
+        let mut measured = vec![];
+
+        for &counter in &bench.perf_counters {
+            let measurement = PerfMeasurement::new(counter);
+            // Falling back to wall time would only repeat the main measurement.
+            if !measurement.is_counting() {
+                continue;
+            }
+
+            let mut counter_criterion = std::mem::take(c).with_measurement(measurement);
+            let mut group = self.benchmark_group(&mut counter_criterion, &perf_group_id(&bench.id, counter));
+            for implementation in bench.implementations.iter() {
+                simd::with_forced(implementation.simd(), || {
+                    group.bench_function(implementation.id(), |b| b.iter(move || implementation.run()));
+                });
+            }
+            group.finish();
+            *c = counter_criterion.with_measurement(WallTime);
+
+            measured.push(counter);
+        }
+
+        measured
+    }
+
+    fn measure_memory(&self) -> Result<Vec<MemoryUsage>, BenchmarkError> {
+        let bench = &self.source;
+        let mut results = vec![];
+
         for implementation in bench.implementations.iter() {
-            let id = implementation.id();
-            group.bench_function(id, |b| b.iter(move || implementation.run()));
+            let usage = implementation.measure_memory()?;
+            println!("{}/{} memory: {usage}", bench.id, implementation.id());
+            results.push(usage);
         }
 
-        group.finish();
+        Ok(results)
+    }
+
+    fn collect_results(
+        &self,
+        started_at: SystemTime,
+        memory: &[MemoryUsage],
+        counters: &[PerfCounter],
+    ) -> Result<(), BenchmarkError> {
+        let bench = &self.source;
+
+        for (i, implementation) in bench.implementations.iter().enumerate() {
+            let memory = memory.get(i).copied();
+            self.collect_target_result(implementation.as_ref(), None, None, started_at, memory)?;
+
+            if bench.measure_phases {
+                for phase in implementation.phases() {
+                    self.collect_target_result(implementation.as_ref(), Some(phase), None, started_at, None)?;
+                }
+            }
+            for &counter in counters {
+                self.collect_target_result(implementation.as_ref(), None, Some(counter), started_at, None)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn collect_target_result(
+        &self,
+        implementation: &dyn BenchFn,
+        phase: Option<BenchPhase>,
+        counter: Option<PerfCounter>,
+        started_at: SystemTime,
+        memory: Option<MemoryUsage>,
+    ) -> Result<(), BenchmarkError> {
+        let bench = &self.source;
+        let group_id = match counter {
+            Some(counter) => perf_group_id(&bench.id, counter),
+            None => bench.id.clone(),
+        };
+        let target = match phase {
+            Some(phase) => phase.target_id(implementation.id()),
+            None => implementation.id().to_owned(),
+        };
+        let parameter = bench.in_family.then(|| bench.json_document.size_in_bytes.to_string());
+        let Some((mean, median)) =
+            report::read_criterion_estimates(&group_id, &target, parameter.as_deref(), started_at)?
+        else {
+            return Ok(());
+        };
+        let target = match parameter {
+            Some(parameter) => format!("{target}/{parameter}"),
+            None => target,
+        };
+
+        report::collect(TargetResult {
+            benchset: group_id,
+            dataset: DatasetInfo {
+                name: bench.dataset.name().to_owned(),
+                path: bench.json_document.file_path.clone(),
+                size_in_bytes: bench.json_document.size_in_bytes,
+                checksum: bench.json_document.checksum_hex(),
+            },
+            target,
+            engine: implementation.engine().to_owned(),
+            query: implementation.query().to_owned(),
+            result_type: implementation.result_type(),
+            phase,
+            counter,
+            simd: implementation.uses_simd().then(|| match implementation.simd() {
+                Some(config) => config.force_value().to_owned(),
+                None => simd::current(),
+            }),
+            measure_file_load: bench.measure_file_load,
+            measure_compilation_time: bench.measure_compilation_time,
+            throughput_bytes: u64::try_from(bench.json_document.size_in_bytes).unwrap(),
+            throughput_records: bench.records.map(|r| u64::try_from(r).unwrap()),
+            mean,
+            median,
+            memory,
+        });
+
+        Ok(())
     }
 }
 
@@ -101,10 +406,17 @@ impl Benchset {
                 measurement_time,
                 sample_count,
             },
+            dataset,
             json_document: json_file,
             implementations: vec![],
             measure_file_load: true,
             measure_compilation_time: false,
+            measure_phases: false,
+            perf_counters: vec![],
+            simd_configs: vec![],
+            verify_results: true,
+            in_family: false,
+            records: None,
         })
     }
 
@@ -122,32 +434,174 @@ impl Benchset {
         }
     }
 
+    /// In addition to the whole target, time each of its phases separately:
+    /// loading the file and compiling the query (if they are measured at all) and running the query.
+    pub fn measure_phases_separately(self) -> Self {
+        Self {
+            measure_phases: true,
+            ..self
+        }
+    }
+
+    /// In addition to wall time, measure all targets with the given hardware performance counters.
+    /// Counters that are not available are skipped with a warning.
+    pub fn measure_perf_counters(self, counters: &[PerfCounter]) -> Self {
+        Self {
+            perf_counters: counters.to_vec(),
+            ..self
+        }
+    }
+
+    /// Benchmark every rsonpath target added after this call once for each SIMD configuration available
+    /// on this machine, with the name of the configuration appended to the target id, e.g. `rsonpath_mmap[avx2]`.
+    pub fn compare_simd_configurations(self) -> Self {
+        Self {
+            simd_configs: simd::available(),
+            ..self
+        }
+    }
+
+    /// Treat the dataset as JSON Lines and apply the query to each record separately, instead of to the whole file.
+    /// Throughput is reported in records per second in addition to bytes per second.
+    ///
+    /// Has to be called before adding targets. Only targets reading the input into memory support this,
+    /// so mmap, buffered and compressed rsonpath targets are rejected.
+    pub fn query_each_record(self) -> Result<Self, BenchmarkError> {
+        if self.dataset.format() != DatasetFormat::JsonLines {
+            return Err(BenchmarkError::NotJsonLines(self.dataset.name()));
+        }
+        let contents = fs::read_to_string(&self.json_document.file_path).map_err(BenchmarkError::RecordsError)?;
+        let records = implementation::records(&contents).count();
+
+        Ok(Self {
+            records: Some(records),
+            ..self
+        })
+    }
+
+    pub fn do_not_verify_results(self) -> Self {
+        Self {
+            verify_results: false,
+            ..self
+        }
+    }
+
+    /// Add the target with its default id. Targets depending on the SIMD configuration of rsonpath
+    /// get the detected SIMD level appended, e.g. `rsonpath_mmap[avx2]`.
     pub fn add_target(mut self, target: BenchTarget<'_>) -> Result<Self, BenchmarkError> {
-        let bench_fn = target.to_bench_fn(
-            &self.json_document.file_path,
-            !self.measure_file_load,
-            !self.measure_compilation_time,
-        )?;
+        self.prepare_input(&target)?;
+        let mut bench_fn = if self.records.is_some() {
+            target.to_record_bench_fn(
+                &self.json_document.file_path,
+                !self.measure_file_load,
+                !self.measure_compilation_time,
+                None,
+            )?
+        } else {
+            target.to_bench_fn(
+                &self.json_document.file_path,
+                !self.measure_file_load,
+                !self.measure_compilation_time,
+            )?
+        };
+        if bench_fn.uses_simd() && !self.simd_configs.is_empty() {
+            let id = bench_fn.id().to_owned();
+            return self.add_target_for_each_simd_config(target, &id);
+        }
+        if bench_fn.uses_simd() {
+            let id = simd::target_id(bench_fn.id(), &simd::current_level());
+            bench_fn.set_id(id);
+        }
         self.implementations.push(bench_fn);
         Ok(self)
     }
 
-    pub fn add_target_with_id(mut self, target: BenchTarget<'_>, id: &'static str) -> Result<Self, BenchmarkError> {
-        let bench_fn = target.to_bench_fn_with_id(
-            &self.json_document.file_path,
-            !self.measure_file_load,
-            !self.measure_compilation_time,
-            id,
-        )?;
+    /// Add the target with a custom id. The SIMD level is appended as in [`Benchset::add_target`].
+    pub fn add_target_with_id(mut self, target: BenchTarget<'_>, id: &str) -> Result<Self, BenchmarkError> {
+        self.prepare_input(&target)?;
+        let mut bench_fn = if self.records.is_some() {
+            target.to_record_bench_fn(
+                &self.json_document.file_path,
+                !self.measure_file_load,
+                !self.measure_compilation_time,
+                Some(id),
+            )?
+        } else {
+            target.to_bench_fn_with_id(
+                &self.json_document.file_path,
+                !self.measure_file_load,
+                !self.measure_compilation_time,
+                id,
+            )?
+        };
+        if bench_fn.uses_simd() && !self.simd_configs.is_empty() {
+            return self.add_target_for_each_simd_config(target, id);
+        }
+        if bench_fn.uses_simd() {
+            bench_fn.set_id(simd::target_id(id, &simd::current_level()));
+        }
         self.implementations.push(bench_fn);
         Ok(self)
     }
 
+    /// Create the compressed copy of the dataset for targets reading it.
+    fn prepare_input(&self, target: &BenchTarget<'_>) -> Result<(), BenchmarkError> {
+        if self.records.is_none() && self.dataset.format() == DatasetFormat::JsonLines {
+            return Err(BenchmarkError::JsonLinesDocument(self.dataset.name()));
+        }
+        if let BenchTarget::RsonpathCompressed(_, compression, _) = target {
+            self.dataset.compress(*compression)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_target_for_each_simd_config(mut self, target: BenchTarget<'_>, id: &str) -> Result<Self, BenchmarkError> {
+        for config in &self.simd_configs {
+            let id = simd::target_id(id, config.name());
+            let bench_fn = simd::with_forced(Some(config), || {
+                if self.records.is_some() {
+                    target.to_record_bench_fn(
+                        &self.json_document.file_path,
+                        !self.measure_file_load,
+                        !self.measure_compilation_time,
+                        Some(&id),
+                    )
+                } else {
+                    target.to_bench_fn_with_id(
+                        &self.json_document.file_path,
+                        !self.measure_file_load,
+                        !self.measure_compilation_time,
+                        &id,
+                    )
+                }
+            })?;
+            self.implementations.push(Box::new(SimdForced {
+                inner: bench_fn,
+                config: config.clone(),
+            }));
+        }
+        Ok(self)
+    }
+
     pub fn add_rsonpath_with_all_result_types(self, query: &str) -> Result<Self, BenchmarkError> {
         self.add_target(BenchTarget::Rsonpath(query, ResultType::Full))?
             .add_target(BenchTarget::Rsonpath(query, ResultType::Count))?
+            .add_target(BenchTarget::Rsonpath(query, ResultType::Indices))?
+            .add_target(BenchTarget::Rsonpath(query, ResultType::ApproxSpans))?
             .add_target(BenchTarget::RsonpathMmap(query, ResultType::Full))?
-            .add_target(BenchTarget::RsonpathMmap(query, ResultType::Count))
+            .add_target(BenchTarget::RsonpathMmap(query, ResultType::Count))?
+            .add_target(BenchTarget::RsonpathMmap(query, ResultType::Indices))?
+            .add_target(BenchTarget::RsonpathMmap(query, ResultType::ApproxSpans))
+    }
+
+    /// Add rsonpath reading the file through a buffered reader, with all result types,
+    /// to compare streaming input with the owned and mmap inputs.
+    pub fn add_rsonpath_buffered(self, query: &str) -> Result<Self, BenchmarkError> {
+        self.add_target(BenchTarget::RsonpathBuffered(query, ResultType::Full))?
+            .add_target(BenchTarget::RsonpathBuffered(query, ResultType::Count))?
+            .add_target(BenchTarget::RsonpathBuffered(query, ResultType::Indices))?
+            .add_target(BenchTarget::RsonpathBuffered(query, ResultType::ApproxSpans))
     }
 
     pub fn add_all_targets_except_jsurfer(self, query: &str) -> Result<Self, BenchmarkError> {
@@ -156,25 +610,83 @@ impl Benchset {
             .add_target(BenchTarget::SerdeJsonPath(query))
     }
 
+    /// Add rsonpath and all other engines. JSurfer is skipped with a notice if the `jsurfer` feature is disabled.
     pub fn add_all_targets(self, query: &str) -> Result<Self, BenchmarkError> {
-        self.add_target(BenchTarget::RsonpathMmap(query, ResultType::Full))?
-            .add_target(BenchTarget::JSurfer(query))?
+        let benchset = self.add_target(BenchTarget::RsonpathMmap(query, ResultType::Full))?;
+        #[cfg(feature = "jsurfer")]
+        let benchset = benchset.add_target(BenchTarget::JSurfer(query))?;
+        #[cfg(not(feature = "jsurfer"))]
+        println!("{}: skipping JSurfer, the `jsurfer` feature is disabled", benchset.id);
+
+        benchset
             .add_target(BenchTarget::JsonpathRust(query))?
             .add_target(BenchTarget::SerdeJsonPath(query))
     }
 
+    /// Add the serde_json baselines: parsing into a `Value` and evaluating the query, and parsing alone.
+    pub fn add_serde_json_baselines(self, query: &str) -> Result<Self, BenchmarkError> {
+        self.add_target(BenchTarget::SerdeJsonValue(query))?
+            .add_target(BenchTarget::SerdeJsonIgnoredAny(query))
+    }
+
     pub fn add_rust_native_targets(self, query: &str) -> Result<Self, BenchmarkError> {
         self.add_target(BenchTarget::RsonpathMmap(query, ResultType::Full))?
             .add_target(BenchTarget::JsonpathRust(query))?
             .add_target(BenchTarget::SerdeJsonPath(query))
     }
 
-    pub fn finish(self) -> ConfiguredBenchset {
-        ConfiguredBenchset { source: self }
+    pub fn finish(self) -> Result<ConfiguredBenchset, BenchmarkError> {
+        if let Some((first, second)) = report::find_directory_collision(self.implementations.iter().map(|i| i.id())) {
+            return Err(BenchmarkError::DuplicateTargetId(
+                self.id.clone(),
+                first.to_owned(),
+                second.to_owned(),
+            ));
+        }
+        if self.verify_results {
+            self.verify()?;
+        }
+
+        Ok(ConfiguredBenchset { source: self })
+    }
+
+    /// Check every target against an earlier one with the same query. Counts are all compared transitively,
+    /// values against the first earlier target that also returned values, so that count-only targets
+    /// coming first do not hide value differences between the others.
+    fn verify(&self) -> Result<(), BenchmarkError> {
+        let mut references: Vec<(&str, &str, ResultSummary)> = vec![];
+
+        for implementation in self.implementations.iter().filter(|i| i.evaluates_query()) {
+            let summary = implementation.summarize()?;
+            let same_query = || {
+                references
+                    .iter()
+                    .filter(|(query, _, _)| *query == implementation.query())
+            };
+            let reference = same_query()
+                .find(|(_, _, expected)| expected.values().is_some() && summary.values().is_some())
+                .or_else(|| same_query().next());
+
+            if let Some((_, reference_id, expected)) = reference {
+                verification::compare(reference_id, expected, implementation.id(), &summary).map_err(|err| {
+                    BenchmarkError::ResultMismatch(Box::new(ResultMismatchDetails {
+                        benchset: self.id.clone(),
+                        query: implementation.query().to_owned(),
+                        mismatch: err,
+                    }))
+                })?;
+            }
+            references.push((implementation.query(), implementation.id(), summary));
+        }
+
+        Ok(())
     }
 }
 
 trait Target {
+    /// Id of the target when added with [`Benchset::add_target`].
+    fn default_id(&self) -> String;
+
     fn to_bench_fn(
         self,
         file_path: &str,
@@ -187,84 +699,163 @@ trait Target {
         file_path: &str,
         load_ahead_of_time: bool,
         compile_ahead_of_time: bool,
-        id: &'static str,
+        id: &str,
+    ) -> Result<Box<dyn BenchFn>, BenchmarkError>;
+
+    /// Target applying the query to each record of a JSON Lines file, with the default id if `id` is not given.
+    fn to_record_bench_fn(
+        self,
+        file_path: &str,
+        load_ahead_of_time: bool,
+        compile_ahead_of_time: bool,
+        id: Option<&str>,
     ) -> Result<Box<dyn BenchFn>, BenchmarkError>;
 }
 
 impl<'a> Target for BenchTarget<'a> {
+    fn default_id(&self) -> String {
+        match *self {
+            BenchTarget::Rsonpath(_, ResultType::Full) => Rsonpath::id().to_owned(),
+            BenchTarget::Rsonpath(_, ResultType::Count) => RsonpathCount::id().to_owned(),
+            BenchTarget::Rsonpath(_, ResultType::Indices) => RsonpathIndices::id().to_owned(),
+            BenchTarget::Rsonpath(_, ResultType::ApproxSpans) => RsonpathApproxSpans::id().to_owned(),
+            BenchTarget::RsonpathMmap(_, ResultType::Full) => RsonpathMmap::id().to_owned(),
+            BenchTarget::RsonpathMmap(_, ResultType::Count) => RsonpathMmapCount::id().to_owned(),
+            BenchTarget::RsonpathMmap(_, ResultType::Indices) => RsonpathMmapIndices::id().to_owned(),
+            BenchTarget::RsonpathMmap(_, ResultType::ApproxSpans) => RsonpathMmapApproxSpans::id().to_owned(),
+            BenchTarget::RsonpathBuffered(_, ResultType::Full) => RsonpathBuffered::id().to_owned(),
+            BenchTarget::RsonpathBuffered(_, ResultType::Count) => RsonpathBufferedCount::id().to_owned(),
+            BenchTarget::RsonpathBuffered(_, ResultType::Indices) => RsonpathBufferedIndices::id().to_owned(),
+            BenchTarget::RsonpathBuffered(_, ResultType::ApproxSpans) => RsonpathBufferedApproxSpans::id().to_owned(),
+            BenchTarget::RsonpathWithSink(_, collection) => id_with_sink::<Rsonpath>(collection),
+            BenchTarget::RsonpathMmapWithSink(_, collection) => id_with_sink::<RsonpathMmap>(collection),
+            BenchTarget::RsonpathBufferedWithSink(_, collection) => id_with_sink::<RsonpathBuffered>(collection),
+            BenchTarget::RsonpathCompressed(_, compression, result_type) => {
+                id_with_compression(compression, result_type)
+            }
+            #[cfg(feature = "jsurfer")]
+            BenchTarget::JSurfer(_) => JSurfer::id().to_owned(),
+            BenchTarget::JsonpathRust(_) => JsonpathRust::id().to_owned(),
+            BenchTarget::SerdeJsonPath(_) => SerdeJsonPath::id().to_owned(),
+            #[cfg(feature = "jsonpath_lib")]
+            BenchTarget::JsonpathLib(_) => JsonpathLib::id().to_owned(),
+            BenchTarget::Jaq(_) => Jaq::id().to_owned(),
+            #[cfg(feature = "jsonski")]
+            BenchTarget::JsonSki(_) => JsonSki::id().to_owned(),
+            BenchTarget::External(_, command) => command.name().to_owned(),
+            BenchTarget::SerdeJsonValue(_) => SerdeJsonValue::id().to_owned(),
+            BenchTarget::SerdeJsonIgnoredAny(_) => SerdeJsonIgnoredAny::id().to_owned(),
+        }
+    }
+
     fn to_bench_fn(
         self,
         file_path: &str,
         load_ahead_of_time: bool,
         compile_ahead_of_time: bool,
+    ) -> Result<Box<dyn BenchFn>, BenchmarkError> {
+        let id = self.default_id();
+        self.to_bench_fn_with_id(file_path, load_ahead_of_time, compile_ahead_of_time, &id)
+    }
+
+    fn to_bench_fn_with_id(
+        self,
+        file_path: &str,
+        load_ahead_of_time: bool,
+        compile_ahead_of_time: bool,
+        id: &str,
     ) -> Result<Box<dyn BenchFn>, BenchmarkError> {
         match self {
             BenchTarget::Rsonpath(q, ResultType::Full) => {
                 let rsonpath = Rsonpath::new()?;
-                let prepared = prepare(rsonpath, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
             BenchTarget::Rsonpath(q, ResultType::Count) => {
                 let rsonpath = RsonpathCount::new()?;
-                let prepared = prepare(rsonpath, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::Rsonpath(q, ResultType::Indices) => {
+                let rsonpath = RsonpathIndices::new()?;
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::Rsonpath(q, ResultType::ApproxSpans) => {
+                let rsonpath = RsonpathApproxSpans::new()?;
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
             BenchTarget::RsonpathMmap(q, ResultType::Full) => {
                 let rsonpath = RsonpathMmap::new()?;
-                let prepared = prepare(rsonpath, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
             BenchTarget::RsonpathMmap(q, ResultType::Count) => {
                 let rsonpath = RsonpathMmapCount::new()?;
-                let prepared = prepare(rsonpath, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
-            BenchTarget::JSurfer(q) => {
-                let jsurfer = JSurfer::new()?;
-                let prepared = prepare(jsurfer, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+            BenchTarget::RsonpathMmap(q, ResultType::Indices) => {
+                let rsonpath = RsonpathMmapIndices::new()?;
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
-            BenchTarget::JsonpathRust(q) => {
-                let jsonpath_rust = JsonpathRust::new()?;
-                let prepared = prepare(jsonpath_rust, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+            BenchTarget::RsonpathMmap(q, ResultType::ApproxSpans) => {
+                let rsonpath = RsonpathMmapApproxSpans::new()?;
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
-            BenchTarget::SerdeJsonPath(q) => {
-                let serde_json_path = SerdeJsonPath::new()?;
-                let prepared = prepare(serde_json_path, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+            BenchTarget::RsonpathBuffered(q, ResultType::Full) => {
+                let rsonpath = RsonpathBuffered::new()?;
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
-        }
-    }
-
-    fn to_bench_fn_with_id(
-        self,
-        file_path: &str,
-        load_ahead_of_time: bool,
-        compile_ahead_of_time: bool,
-        id: &'static str,
-    ) -> Result<Box<dyn BenchFn>, BenchmarkError> {
-        match self {
-            BenchTarget::Rsonpath(q, ResultType::Full) => {
-                let rsonpath = Rsonpath::new()?;
+            BenchTarget::RsonpathBuffered(q, ResultType::Count) => {
+                let rsonpath = RsonpathBufferedCount::new()?;
                 let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
-            BenchTarget::Rsonpath(q, ResultType::Count) => {
-                let rsonpath = RsonpathCount::new()?;
+            BenchTarget::RsonpathBuffered(q, ResultType::Indices) => {
+                let rsonpath = RsonpathBufferedIndices::new()?;
                 let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
-            BenchTarget::RsonpathMmap(q, ResultType::Full) => {
-                let rsonpath = RsonpathMmap::new()?;
+            BenchTarget::RsonpathBuffered(q, ResultType::ApproxSpans) => {
+                let rsonpath = RsonpathBufferedApproxSpans::new()?;
                 let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
-            BenchTarget::RsonpathMmap(q, ResultType::Count) => {
-                let rsonpath = RsonpathMmapCount::new()?;
+            BenchTarget::RsonpathWithSink(q, collection) => {
+                let rsonpath = Rsonpath::with_collection(collection);
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::RsonpathMmapWithSink(q, collection) => {
+                let rsonpath = RsonpathMmap::with_collection(collection);
                 let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
                 Ok(Box::new(prepared))
             }
+            BenchTarget::RsonpathBufferedWithSink(q, collection) => {
+                let rsonpath = RsonpathBuffered::with_collection(collection);
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::RsonpathCompressed(q, compression, ResultType::Full) => {
+                let rsonpath = RsonpathCompressed::with_compression(compression);
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::RsonpathCompressed(q, compression, ResultType::Count) => {
+                let rsonpath = RsonpathCompressedCount::with_compression(compression);
+                let prepared = prepare_with_id(rsonpath, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::RsonpathCompressed(_, _, result_type) => Err(BenchmarkError::UnsupportedResultType(
+                RsonpathCompressed::id(),
+                result_type,
+            )),
+            #[cfg(feature = "jsurfer")]
             BenchTarget::JSurfer(q) => {
                 let jsurfer = JSurfer::new()?;
                 let prepared = prepare_with_id(jsurfer, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
@@ -294,19 +885,216 @@ impl<'a> Target for BenchTarget<'a> {
                 )?;
                 Ok(Box::new(prepared))
             }
+            #[cfg(feature = "jsonpath_lib")]
+            BenchTarget::JsonpathLib(q) => {
+                let jsonpath_lib = JsonpathLib::new()?;
+                let prepared = prepare_with_id(
+                    jsonpath_lib,
+                    id,
+                    file_path,
+                    q,
+                    load_ahead_of_time,
+                    compile_ahead_of_time,
+                )?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::Jaq(q) => {
+                let jaq = Jaq::new()?;
+                let prepared = prepare_with_id(jaq, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            #[cfg(feature = "jsonski")]
+            BenchTarget::JsonSki(q) => {
+                let jsonski = JsonSki::new()?;
+                let prepared = prepare_with_id(jsonski, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::External(q, command) => {
+                let engine = ExternalEngine::spawn(command)?;
+                let prepared = prepare_with_id(engine, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::SerdeJsonValue(q) => {
+                let serde_json = SerdeJsonValue::new()?;
+                let prepared =
+                    prepare_with_id(serde_json, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
+            BenchTarget::SerdeJsonIgnoredAny(q) => {
+                let serde_json = SerdeJsonIgnoredAny::new()?;
+                let prepared =
+                    prepare_with_id(serde_json, id, file_path, q, load_ahead_of_time, compile_ahead_of_time)?;
+                Ok(Box::new(prepared))
+            }
         }
     }
+
+    fn to_record_bench_fn(
+        self,
+        file_path: &str,
+        load_ahead_of_time: bool,
+        compile_ahead_of_time: bool,
+        id: Option<&str>,
+    ) -> Result<Box<dyn BenchFn>, BenchmarkError> {
+        let (load, compile) = (load_ahead_of_time, compile_ahead_of_time);
+
+        match self {
+            BenchTarget::Rsonpath(q, ResultType::Full) => {
+                prepare_records(Rsonpath::new()?, id, file_path, q, load, compile)
+            }
+            BenchTarget::Rsonpath(q, ResultType::Count) => {
+                prepare_records(RsonpathCount::new()?, id, file_path, q, load, compile)
+            }
+            BenchTarget::Rsonpath(q, ResultType::Indices) => {
+                prepare_records(RsonpathIndices::new()?, id, file_path, q, load, compile)
+            }
+            BenchTarget::Rsonpath(q, ResultType::ApproxSpans) => {
+                prepare_records(RsonpathApproxSpans::new()?, id, file_path, q, load, compile)
+            }
+            BenchTarget::RsonpathWithSink(q, collection) => {
+                let id = id.map_or_else(|| id_with_sink::<Rsonpath>(collection), str::to_owned);
+                let rsonpath = Rsonpath::with_collection(collection);
+                prepare_records(rsonpath, Some(&id), file_path, q, load, compile)
+            }
+            #[cfg(feature = "jsurfer")]
+            BenchTarget::JSurfer(q) => prepare_records(JSurfer::new()?, id, file_path, q, load, compile),
+            BenchTarget::JsonpathRust(q) => prepare_records(JsonpathRust::new()?, id, file_path, q, load, compile),
+            BenchTarget::SerdeJsonPath(q) => prepare_records(SerdeJsonPath::new()?, id, file_path, q, load, compile),
+            #[cfg(feature = "jsonpath_lib")]
+            BenchTarget::JsonpathLib(q) => prepare_records(JsonpathLib::new()?, id, file_path, q, load, compile),
+            BenchTarget::Jaq(q) => prepare_records(Jaq::new()?, id, file_path, q, load, compile),
+            BenchTarget::External(q, command) => {
+                let id = id.unwrap_or(command.name());
+                prepare_records(ExternalEngine::spawn(command)?, Some(id), file_path, q, load, compile)
+            }
+            BenchTarget::SerdeJsonValue(q) => prepare_records(SerdeJsonValue::new()?, id, file_path, q, load, compile),
+            BenchTarget::SerdeJsonIgnoredAny(q) => {
+                prepare_records(SerdeJsonIgnoredAny::new()?, id, file_path, q, load, compile)
+            }
+            BenchTarget::RsonpathMmap(_, _) | BenchTarget::RsonpathMmapWithSink(_, _) => {
+                Err(BenchmarkError::UnsupportedPerRecord(RsonpathMmap::id()))
+            }
+            BenchTarget::RsonpathBuffered(_, _) | BenchTarget::RsonpathBufferedWithSink(_, _) => {
+                Err(BenchmarkError::UnsupportedPerRecord(RsonpathBuffered::id()))
+            }
+            BenchTarget::RsonpathCompressed(_, _, _) => {
+                Err(BenchmarkError::UnsupportedPerRecord(RsonpathCompressed::id()))
+            }
+            #[cfg(feature = "jsonski")]
+            BenchTarget::JsonSki(_) => Err(BenchmarkError::UnsupportedPerRecord(JsonSki::id())),
+        }
+    }
+}
+
+/// Prepare a target applying the query to each record, see [`Benchset::query_each_record`].
+fn prepare_records<I: LoadRecord + 'static>(
+    implementation: I,
+    id: Option<&str>,
+    file_path: &str,
+    query: &str,
+    load_ahead_of_time: bool,
+    compile_ahead_of_time: bool,
+) -> Result<Box<dyn BenchFn>, BenchmarkError>
+where
+    BenchmarkError: From<I::Error>,
+{
+    let prepared = prepare_records_with_id(
+        implementation,
+        id.unwrap_or(I::id()),
+        file_path,
+        query,
+        load_ahead_of_time,
+        compile_ahead_of_time,
+    )?;
+
+    Ok(Box::new(prepared))
+}
+
+fn perf_group_id(benchset_id: &str, counter: PerfCounter) -> String {
+    format!("{benchset_id}_{counter}")
+}
+
+fn id_with_compression(compression: Compression, result_type: ResultType) -> String {
+    match result_type {
+        ResultType::Full => format!("rsonpath_{compression}"),
+        ResultType::Count => format!("rsonpath_{compression}_count"),
+        ResultType::Indices => format!("rsonpath_{compression}_indices"),
+        ResultType::ApproxSpans => format!("rsonpath_{compression}_approx_spans"),
+    }
+}
+
+fn id_with_sink<I: Implementation>(collection: MatchCollection) -> String {
+    match collection.id_suffix() {
+        Some(suffix) => format!("{}_{suffix}", I::id()),
+        None => I::id().to_owned(),
+    }
 }
 
 trait BenchFn {
     fn id(&self) -> &str;
 
+    fn set_id(&mut self, id: String);
+
+    fn engine(&self) -> &str;
+
+    fn query(&self) -> &str;
+
+    fn result_type(&self) -> ResultType;
+
+    fn uses_simd(&self) -> bool;
+
+    fn evaluates_query(&self) -> bool;
+
+    fn simd(&self) -> Option<&SimdConfig> {
+        None
+    }
+
     fn run(&self);
+
+    /// Measure [`BenchFn::run`] with wall time, or with the time reported by the engine if it has one.
+    fn bench(&self, b: &mut Bencher<'_, WallTime>) {
+        b.iter(|| self.run())
+    }
+
+    fn summarize(&self) -> Result<ResultSummary, BenchmarkError>;
+
+    fn measure_memory(&self) -> Result<MemoryUsage, BenchmarkError>;
+
+    fn phases(&self) -> Vec<BenchPhase>;
+
+    fn bench_phases(&self, group: &mut BenchmarkGroup<'_, WallTime>);
 }
 
-impl<I: Implementation> BenchFn for PreparedQuery<I> {
+impl<I: Implementation> BenchFn for PreparedQuery<I>
+where
+    BenchmarkError: From<I::Error>,
+{
     fn id(&self) -> &str {
-        self.id
+        &self.id
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn engine(&self) -> &str {
+        I::id()
+    }
+
+    fn query(&self) -> &str {
+        &self.query_string
+    }
+
+    fn result_type(&self) -> ResultType {
+        I::result_type()
+    }
+
+    fn uses_simd(&self) -> bool {
+        I::uses_simd()
+    }
+
+    fn evaluates_query(&self) -> bool {
+        I::evaluates_query()
     }
 
     fn run(&self) {
@@ -331,6 +1119,330 @@ impl<I: Implementation> BenchFn for PreparedQuery<I> {
         let result = self.implementation.run(q, f).unwrap();
         criterion::black_box(result);
     }
+
+    fn bench(&self, b: &mut Bencher<'_, WallTime>) {
+        match (&self.file, &self.query) {
+            (implementation::File::AlreadyLoaded(f), implementation::Query::AlreadyCompiled(q))
+                if I::reports_time() =>
+            {
+                b.iter_custom(|iters| (0..iters).map(|_| timed_run(&self.implementation, q, f)).sum())
+            }
+            _ => b.iter(|| self.run()),
+        }
+    }
+
+    fn summarize(&self) -> Result<ResultSummary, BenchmarkError> {
+        let f_storage;
+        let q_storage;
+
+        let f = match &self.file {
+            implementation::File::NeedToLoad(file_path) => {
+                f_storage = self.implementation.load_file(file_path)?;
+                &f_storage
+            }
+            implementation::File::AlreadyLoaded(f) => f,
+        };
+        let q = match &self.query {
+            implementation::Query::NeedToCompile(query_string) => {
+                q_storage = self.implementation.compile_query(query_string)?;
+                &q_storage
+            }
+            implementation::Query::AlreadyCompiled(q) => q,
+        };
+
+        Ok(self.implementation.summarize(q, f)?)
+    }
+
+    fn measure_memory(&self) -> Result<MemoryUsage, BenchmarkError> {
+        let phase = Phase::start();
+        let f = self.implementation.load_file(&self.file_path)?;
+        let load = phase.finish();
+
+        let phase = Phase::start();
+        let q = self.implementation.compile_query(&self.query_string)?;
+        let compile = phase.finish();
+
+        let phase = Phase::start();
+        let result = self.implementation.run(&q, &f)?;
+        let run = phase.finish();
+        drop(result);
+
+        Ok(MemoryUsage { load, compile, run })
+    }
+
+    fn phases(&self) -> Vec<BenchPhase> {
+        let mut phases = vec![];
+        if let implementation::File::NeedToLoad(_) = self.file {
+            phases.push(BenchPhase::Load);
+        }
+        if let implementation::Query::NeedToCompile(_) = self.query {
+            phases.push(BenchPhase::Compile);
+        }
+        phases.push(BenchPhase::Run);
+
+        phases
+    }
+
+    fn bench_phases(&self, group: &mut BenchmarkGroup<'_, WallTime>) {
+        let f_storage;
+        let q_storage;
+
+        let f = match &self.file {
+            implementation::File::NeedToLoad(file_path) => {
+                group.bench_function(BenchPhase::Load.target_id(&self.id), |b| {
+                    b.iter_with_large_drop(|| self.implementation.load_file(file_path).unwrap())
+                });
+                f_storage = self.implementation.load_file(file_path).unwrap();
+                &f_storage
+            }
+            implementation::File::AlreadyLoaded(f) => f,
+        };
+        let q = match &self.query {
+            implementation::Query::NeedToCompile(query_string) => {
+                group.bench_function(BenchPhase::Compile.target_id(&self.id), |b| {
+                    b.iter_with_large_drop(|| self.implementation.compile_query(query_string).unwrap())
+                });
+                q_storage = self.implementation.compile_query(query_string).unwrap();
+                &q_storage
+            }
+            implementation::Query::AlreadyCompiled(q) => q,
+        };
+
+        group.bench_function(BenchPhase::Run.target_id(&self.id), |b| {
+            if I::reports_time() {
+                b.iter_custom(|iters| (0..iters).map(|_| timed_run(&self.implementation, q, f)).sum())
+            } else {
+                b.iter(|| self.implementation.run(q, f).unwrap())
+            }
+        });
+    }
+}
+
+/// Run the query once and return the time reported by the engine, or the wall time if it did not report any.
+fn timed_run<I: Implementation>(implementation: &I, query: &I::Query, file: &I::File) -> Duration {
+    let start = Instant::now();
+    let result = implementation.run(query, file).unwrap();
+    let elapsed = start.elapsed();
+
+    I::reported_time(&result).unwrap_or(elapsed)
+}
+
+impl<I: LoadRecord> BenchFn for PreparedRecords<I>
+where
+    BenchmarkError: From<I::Error>,
+{
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn engine(&self) -> &str {
+        I::id()
+    }
+
+    fn query(&self) -> &str {
+        &self.query_string
+    }
+
+    fn result_type(&self) -> ResultType {
+        I::result_type()
+    }
+
+    fn uses_simd(&self) -> bool {
+        I::uses_simd()
+    }
+
+    fn evaluates_query(&self) -> bool {
+        I::evaluates_query()
+    }
+
+    fn run(&self) {
+        let r_storage;
+        let q_storage;
+
+        let records = match &self.records {
+            implementation::File::NeedToLoad(file_path) => {
+                r_storage = load_records(&self.implementation, file_path).unwrap();
+                &r_storage
+            }
+            implementation::File::AlreadyLoaded(records) => records,
+        };
+        let q = match &self.query {
+            implementation::Query::NeedToCompile(query_string) => {
+                q_storage = self.implementation.compile_query(query_string).unwrap();
+                &q_storage
+            }
+            implementation::Query::AlreadyCompiled(q) => q,
+        };
+
+        for record in records {
+            let result = self.implementation.run(q, record).unwrap();
+            criterion::black_box(result);
+        }
+    }
+
+    fn summarize(&self) -> Result<ResultSummary, BenchmarkError> {
+        let r_storage;
+        let q_storage;
+
+        let records = match &self.records {
+            implementation::File::NeedToLoad(file_path) => {
+                r_storage = load_records(&self.implementation, file_path)?;
+                &r_storage
+            }
+            implementation::File::AlreadyLoaded(records) => records,
+        };
+        let q = match &self.query {
+            implementation::Query::NeedToCompile(query_string) => {
+                q_storage = self.implementation.compile_query(query_string)?;
+                &q_storage
+            }
+            implementation::Query::AlreadyCompiled(q) => q,
+        };
+
+        let summaries = records
+            .iter()
+            .map(|record| self.implementation.summarize(q, record))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ResultSummary::combine(summaries))
+    }
+
+    fn measure_memory(&self) -> Result<MemoryUsage, BenchmarkError> {
+        let phase = Phase::start();
+        let records = load_records(&self.implementation, &self.file_path)?;
+        let load = phase.finish();
+
+        let phase = Phase::start();
+        let q = self.implementation.compile_query(&self.query_string)?;
+        let compile = phase.finish();
+
+        let phase = Phase::start();
+        for record in &records {
+            let result = self.implementation.run(&q, record)?;
+            drop(result);
+        }
+        let run = phase.finish();
+
+        Ok(MemoryUsage { load, compile, run })
+    }
+
+    fn phases(&self) -> Vec<BenchPhase> {
+        let mut phases = vec![];
+        if let implementation::File::NeedToLoad(_) = self.records {
+            phases.push(BenchPhase::Load);
+        }
+        if let implementation::Query::NeedToCompile(_) = self.query {
+            phases.push(BenchPhase::Compile);
+        }
+        phases.push(BenchPhase::Run);
+
+        phases
+    }
+
+    fn bench_phases(&self, group: &mut BenchmarkGroup<'_, WallTime>) {
+        let r_storage;
+        let q_storage;
+
+        let records = match &self.records {
+            implementation::File::NeedToLoad(file_path) => {
+                group.bench_function(BenchPhase::Load.target_id(&self.id), |b| {
+                    b.iter_with_large_drop(|| load_records(&self.implementation, file_path).unwrap())
+                });
+                r_storage = load_records(&self.implementation, file_path).unwrap();
+                &r_storage
+            }
+            implementation::File::AlreadyLoaded(records) => records,
+        };
+        let q = match &self.query {
+            implementation::Query::NeedToCompile(query_string) => {
+                group.bench_function(BenchPhase::Compile.target_id(&self.id), |b| {
+                    b.iter_with_large_drop(|| self.implementation.compile_query(query_string).unwrap())
+                });
+                q_storage = self.implementation.compile_query(query_string).unwrap();
+                &q_storage
+            }
+            implementation::Query::AlreadyCompiled(q) => q,
+        };
+
+        group.bench_function(BenchPhase::Run.target_id(&self.id), |b| {
+            b.iter(|| {
+                for record in records {
+                    criterion::black_box(self.implementation.run(q, record).unwrap());
+                }
+            })
+        });
+    }
+}
+
+/// Target compiled and run with rsonpath forced to a specific SIMD configuration.
+///
+/// Running is not wrapped, since setting the environment on every iteration would skew the measurement.
+/// Callers wrap the whole benchmark in [`simd::with_forced`] instead.
+struct SimdForced {
+    inner: Box<dyn BenchFn>,
+    config: SimdConfig,
+}
+
+impl BenchFn for SimdForced {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.inner.set_id(id)
+    }
+
+    fn engine(&self) -> &str {
+        self.inner.engine()
+    }
+
+    fn query(&self) -> &str {
+        self.inner.query()
+    }
+
+    fn result_type(&self) -> ResultType {
+        self.inner.result_type()
+    }
+
+    fn uses_simd(&self) -> bool {
+        true
+    }
+
+    fn evaluates_query(&self) -> bool {
+        self.inner.evaluates_query()
+    }
+
+    fn simd(&self) -> Option<&SimdConfig> {
+        Some(&self.config)
+    }
+
+    fn run(&self) {
+        self.inner.run()
+    }
+
+    fn bench(&self, b: &mut Bencher<'_, WallTime>) {
+        self.inner.bench(b)
+    }
+
+    fn summarize(&self) -> Result<ResultSummary, BenchmarkError> {
+        simd::with_forced(Some(&self.config), || self.inner.summarize())
+    }
+
+    fn measure_memory(&self) -> Result<MemoryUsage, BenchmarkError> {
+        simd::with_forced(Some(&self.config), || self.inner.measure_memory())
+    }
+
+    fn phases(&self) -> Vec<BenchPhase> {
+        self.inner.phases()
+    }
+
+    fn bench_phases(&self, group: &mut BenchmarkGroup<'_, WallTime>) {
+        simd::with_forced(Some(&self.config), || self.inner.bench_phases(group))
+    }
 }
 
 #[derive(Error, Debug)]
@@ -349,6 +1461,7 @@ pub enum BenchmarkError {
         #[from]
         RsonpathError,
     ),
+    #[cfg(feature = "jsurfer")]
     #[error("error preparing JSurfer bench: {0}")]
     JSurferError(
         #[source]
@@ -367,4 +1480,118 @@ pub enum BenchmarkError {
         #[from]
         SerdeJsonPathError,
     ),
+    #[cfg(feature = "jsonpath_lib")]
+    #[error("error preparing JsonpathLib bench: {0}")]
+    JsonpathLib(
+        #[source]
+        #[from]
+        JsonpathLibError,
+    ),
+    #[error("error preparing Jaq bench: {0}")]
+    Jaq(
+        #[source]
+        #[from]
+        JaqError,
+    ),
+    #[cfg(feature = "jsonski")]
+    #[error("error preparing JsonSki bench: {0}")]
+    JsonSki(
+        #[source]
+        #[from]
+        JsonSkiError,
+    ),
+    #[error("error preparing external engine bench: {0}")]
+    External(
+        #[source]
+        #[from]
+        ExternalEngineError,
+    ),
+    #[error("error preparing serde_json baseline bench: {0}")]
+    SerdeJson(
+        #[source]
+        #[from]
+        SerdeJsonError,
+    ),
+    #[error("target {0} does not support result type {1:?}")]
+    UnsupportedResultType(&'static str, ResultType),
+    #[error("target {0} cannot query individual records")]
+    UnsupportedPerRecord(&'static str),
+    #[error("dataset {0} is not in the JSON Lines format and has no records to query")]
+    NotJsonLines(&'static str),
+    #[error("dataset {0} is in the JSON Lines format, its records have to be queried separately")]
+    JsonLinesDocument(&'static str),
+    #[error("error reading records: {0}")]
+    RecordsError(#[source] io::Error),
+    #[error("targets {1} and {2} in benchset {0} have the same id in criterion's output")]
+    DuplicateTargetId(String, String, String),
+    #[error(transparent)]
+    ResultMismatch(Box<ResultMismatchDetails>),
+    #[error("error in benchmark suite: {0}")]
+    SuiteError(
+        #[source]
+        #[from]
+        SuiteError,
+    ),
+    #[error("error producing the benchmark report: {0}")]
+    ReportError(
+        #[source]
+        #[from]
+        ReportError,
+    ),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_on_the_deep_dataset_are_verified() {
+        let benchset = Benchset::new("generated::deep_descendant", dataset::generated_deep())
+            .unwrap()
+            .add_rsonpath_with_all_result_types("$..k0")
+            .unwrap();
+
+        assert!(benchset.finish().is_ok());
+    }
+
+    #[test]
+    fn duplicate_target_ids_are_rejected() {
+        let benchset = Benchset::new("small::az_tenants", dataset::az_tenants())
+            .unwrap()
+            .add_target(BenchTarget::SerdeJsonPath("$..tenantId"))
+            .unwrap()
+            .add_target(BenchTarget::SerdeJsonPath("$[*].tenantId"))
+            .unwrap();
+
+        let result = benchset.finish();
+
+        assert!(matches!(result, Err(BenchmarkError::DuplicateTargetId(_, _, _))));
+    }
+
+    #[test]
+    fn default_and_custom_ids_get_the_same_simd_suffix() {
+        let benchset = Benchset::new("small::az_tenants", dataset::az_tenants())
+            .unwrap()
+            .add_target(BenchTarget::RsonpathMmap("$..tenantId", ResultType::Count))
+            .unwrap()
+            .add_target_with_id(
+                BenchTarget::RsonpathMmap("$..tenantId", ResultType::Full),
+                "rsonpath_descendant",
+            )
+            .unwrap()
+            .add_target_with_id(BenchTarget::SerdeJsonPath("$..tenantId"), "serde_json_path_descendant")
+            .unwrap();
+        let level = simd::current_level();
+
+        let ids: Vec<_> = benchset.implementations.iter().map(|i| i.id().to_owned()).collect();
+
+        assert_eq!(
+            ids,
+            [
+                format!("rsonpath_mmap_count[{level}]"),
+                format!("rsonpath_descendant[{level}]"),
+                "serde_json_path_descendant".to_owned(),
+            ]
+        );
+    }
 }
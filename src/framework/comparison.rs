@@ -0,0 +1,408 @@
+use super::report::{Estimate, Report, TargetResult};
+use std::fmt::Display;
+
+pub struct Comparison {
+    threshold: f64,
+    allow_missing: bool,
+    entries: Vec<ComparisonEntry>,
+}
+
+pub struct ComparisonEntry {
+    pub benchset: String,
+    pub target: String,
+    pub outcome: Outcome,
+}
+
+pub enum Outcome {
+    Compared(Change),
+    MissingInCurrent(Estimate),
+    NewInCurrent(Estimate),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Change {
+    pub baseline: Estimate,
+    pub current: Estimate,
+    pub relative: f64,
+    /// Conservative bounds of the relative change, see [`Change::new`].
+    pub lower_bound: f64,
+    pub upper_bound: f64,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Regressed,
+    Improved,
+    NoChange,
+}
+
+impl Comparison {
+    /// Match results by benchset and target ids and compare their median times.
+    ///
+    /// The `threshold` is a relative change, e.g. `0.05` for 5%. A target is considered regressed only if
+    /// the whole confidence interval of the change lies above the threshold, and improved if it lies below
+    /// its negation. Targets of the baseline missing from the current report fail the comparison,
+    /// unless [allowed](Comparison::allow_missing).
+    pub fn new(baseline: &Report, current: &Report, threshold: f64) -> Self {
+        let mut entries = vec![];
+
+        for base in &baseline.results {
+            let outcome = match find(current, base) {
+                Some(cur) => Outcome::Compared(Change::new(base.median, cur.median, threshold)),
+                None => Outcome::MissingInCurrent(base.median),
+            };
+            entries.push(ComparisonEntry {
+                benchset: base.benchset.clone(),
+                target: base.target.clone(),
+                outcome,
+            });
+        }
+
+        for cur in &current.results {
+            if find(baseline, cur).is_none() {
+                entries.push(ComparisonEntry {
+                    benchset: cur.benchset.clone(),
+                    target: cur.target.clone(),
+                    outcome: Outcome::NewInCurrent(cur.median),
+                })
+            }
+        }
+
+        Self {
+            threshold,
+            allow_missing: false,
+            entries,
+        }
+    }
+
+    /// Do not fail the comparison if targets of the baseline are missing from the current report,
+    /// e.g. when only some of the benches were run.
+    pub fn allow_missing(self) -> Self {
+        Self {
+            allow_missing: true,
+            ..self
+        }
+    }
+
+    pub fn entries(&self) -> &[ComparisonEntry] {
+        &self.entries
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &ComparisonEntry> {
+        self.entries
+            .iter()
+            .filter(|e| matches!(&e.outcome, Outcome::Compared(c) if c.verdict == Verdict::Regressed))
+    }
+
+    pub fn has_regressions(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+
+    pub fn missing(&self) -> impl Iterator<Item = &ComparisonEntry> {
+        self.entries
+            .iter()
+            .filter(|e| matches!(&e.outcome, Outcome::MissingInCurrent(_)))
+    }
+
+    /// Whether any target regressed or, unless allowed, is missing from the current report.
+    pub fn fails(&self) -> bool {
+        self.has_regressions() || (!self.allow_missing && self.missing().next().is_some())
+    }
+}
+
+impl Change {
+    /// Bound the change by dividing opposite ends of the two confidence intervals, i.e. the fastest current time
+    /// by the slowest baseline time and vice versa. Without the bootstrap samples the proper interval of the ratio
+    /// cannot be computed, so this one is deliberately wider, and verdicts err towards no change.
+    fn new(baseline: Estimate, current: Estimate, threshold: f64) -> Self {
+        let relative = current.point_estimate / baseline.point_estimate - 1.0;
+        let lower_bound = current.confidence_interval.lower_bound / baseline.confidence_interval.upper_bound - 1.0;
+        let upper_bound = current.confidence_interval.upper_bound / baseline.confidence_interval.lower_bound - 1.0;
+
+        let verdict = if lower_bound > threshold {
+            Verdict::Regressed
+        } else if upper_bound < -threshold {
+            Verdict::Improved
+        } else {
+            Verdict::NoChange
+        };
+
+        Self {
+            baseline,
+            current,
+            relative,
+            lower_bound,
+            upper_bound,
+            verdict,
+        }
+    }
+}
+
+fn find<'a>(report: &'a Report, result: &TargetResult) -> Option<&'a TargetResult> {
+    report
+        .results
+        .iter()
+        .find(|r| r.benchset == result.benchset && r.target == result.target)
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<[String; 6]> = self
+            .entries
+            .iter()
+            .map(|e| {
+                let benchset = e.benchset.clone();
+                let target = e.target.clone();
+                match &e.outcome {
+                    Outcome::Compared(c) => [
+                        benchset,
+                        target,
+                        format_time(c.baseline.point_estimate),
+                        format_time(c.current.point_estimate),
+                        format!(
+                            "{:+.2}% [{:+.2}%, {:+.2}%]",
+                            c.relative * 100.0,
+                            c.lower_bound * 100.0,
+                            c.upper_bound * 100.0
+                        ),
+                        c.verdict.to_string(),
+                    ],
+                    Outcome::MissingInCurrent(baseline) => [
+                        benchset,
+                        target,
+                        format_time(baseline.point_estimate),
+                        "-".to_owned(),
+                        String::new(),
+                        "missing".to_owned(),
+                    ],
+                    Outcome::NewInCurrent(current) => [
+                        benchset,
+                        target,
+                        "-".to_owned(),
+                        format_time(current.point_estimate),
+                        String::new(),
+                        "new".to_owned(),
+                    ],
+                }
+            })
+            .collect();
+
+        let header = ["benchset", "target", "baseline", "current", "change", "verdict"].map(str::to_owned);
+        let mut widths = header.clone().map(|h| h.len());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let write_row = |f: &mut std::fmt::Formatter<'_>, row: &[String; 6]| {
+            for (i, (cell, width)) in row.iter().zip(widths).enumerate() {
+                if i > 0 {
+                    write!(f, " | ")?;
+                }
+                if i + 1 < row.len() {
+                    write!(f, "{cell:width$}")?;
+                } else {
+                    write!(f, "{cell}")?;
+                }
+            }
+            writeln!(f)
+        };
+
+        write_row(f, &header)?;
+        writeln!(
+            f,
+            "{}",
+            "-".repeat(widths.iter().sum::<usize>() + 3 * (widths.len() - 1))
+        )?;
+        for row in &rows {
+            write_row(f, row)?;
+        }
+
+        let regressions = self.regressions().count();
+        writeln!(
+            f,
+            "\n{regressions} of {} targets regressed by more than {:.2}%.",
+            self.entries.len(),
+            self.threshold * 100.0
+        )?;
+        let missing = self.missing().count();
+        if missing > 0 {
+            writeln!(
+                f,
+                "{missing} targets of the baseline are missing from the current report."
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Regressed => write!(f, "REGRESSED"),
+            Self::Improved => write!(f, "improved"),
+            Self::NoChange => write!(f, "no change"),
+        }
+    }
+}
+
+fn format_time(nanos: f64) -> String {
+    if nanos < 1_000.0 {
+        format!("{nanos:.2} ns")
+    } else if nanos < 1_000_000.0 {
+        format!("{:.2} µs", nanos / 1_000.0)
+    } else if nanos < 1_000_000_000.0 {
+        format!("{:.2} ms", nanos / 1_000_000.0)
+    } else {
+        format!("{:.2} s", nanos / 1_000_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{
+        report::{ConfidenceInterval, DatasetInfo},
+        ResultType,
+    };
+
+    fn estimate(lower_bound: f64, point_estimate: f64, upper_bound: f64) -> Estimate {
+        Estimate {
+            point_estimate,
+            standard_error: 0.0,
+            confidence_interval: ConfidenceInterval {
+                confidence_level: 0.95,
+                lower_bound,
+                upper_bound,
+            },
+        }
+    }
+
+    fn result(target: &str, median: Estimate) -> TargetResult {
+        TargetResult {
+            benchset: "bench".to_owned(),
+            dataset: DatasetInfo {
+                name: "dataset".to_owned(),
+                path: "./data/dataset.json".to_owned(),
+                size_in_bytes: 1000,
+                checksum: String::new(),
+            },
+            target: target.to_owned(),
+            engine: "rsonpath".to_owned(),
+            query: "$..a".to_owned(),
+            result_type: ResultType::Full,
+            phase: None,
+            counter: None,
+            simd: None,
+            measure_file_load: false,
+            measure_compilation_time: false,
+            throughput_bytes: 1000,
+            throughput_records: None,
+            mean: median,
+            median,
+            memory: None,
+        }
+    }
+
+    fn report(results: Vec<TargetResult>) -> Report {
+        Report { results }
+    }
+
+    fn verdict(baseline: Estimate, current: Estimate) -> Verdict {
+        Change::new(baseline, current, 0.05).verdict
+    }
+
+    #[test]
+    fn change_bounds_divide_opposite_ends_of_the_intervals() {
+        let change = Change::new(estimate(90.0, 100.0, 110.0), estimate(99.0, 110.0, 121.0), 0.05);
+
+        assert!((change.relative - 0.1).abs() < 1e-9);
+        assert!((change.lower_bound - (99.0 / 110.0 - 1.0)).abs() < 1e-9);
+        assert!((change.upper_bound - (121.0 / 90.0 - 1.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn verdict_requires_the_whole_interval_past_the_threshold() {
+        let baseline = estimate(95.0, 100.0, 105.0);
+
+        assert_eq!(verdict(baseline, estimate(125.0, 130.0, 135.0)), Verdict::Regressed);
+        assert_eq!(verdict(baseline, estimate(65.0, 70.0, 75.0)), Verdict::Improved);
+        assert_eq!(verdict(baseline, estimate(99.0, 104.0, 109.0)), Verdict::NoChange);
+        // The point estimate regressed by 20%, but the intervals overlap the threshold.
+        assert_eq!(verdict(baseline, estimate(100.0, 120.0, 140.0)), Verdict::NoChange);
+    }
+
+    #[test]
+    fn regressions_fail_the_comparison() {
+        let baseline = report(vec![
+            result("a", estimate(95.0, 100.0, 105.0)),
+            result("b", estimate(95.0, 100.0, 105.0)),
+        ]);
+        let current = report(vec![
+            result("a", estimate(125.0, 130.0, 135.0)),
+            result("b", estimate(65.0, 70.0, 75.0)),
+        ]);
+        let comparison = Comparison::new(&baseline, &current, 0.05);
+
+        let regressed: Vec<_> = comparison.regressions().map(|e| e.target.as_str()).collect();
+        assert_eq!(regressed, ["a"]);
+        assert!(comparison.fails());
+        assert!(comparison.allow_missing().fails());
+    }
+
+    #[test]
+    fn missing_targets_fail_unless_allowed() {
+        let baseline = report(vec![
+            result("a", estimate(95.0, 100.0, 105.0)),
+            result("b", estimate(95.0, 100.0, 105.0)),
+        ]);
+        let current = report(vec![result("a", estimate(95.0, 100.0, 105.0))]);
+        let comparison = Comparison::new(&baseline, &current, 0.05);
+
+        assert!(!comparison.has_regressions());
+        assert_eq!(comparison.missing().count(), 1);
+        assert!(comparison.fails());
+        assert!(!comparison.allow_missing().fails());
+    }
+
+    #[test]
+    fn new_targets_do_not_fail() {
+        let baseline = report(vec![result("a", estimate(95.0, 100.0, 105.0))]);
+        let current = report(vec![
+            result("a", estimate(95.0, 100.0, 105.0)),
+            result("b", estimate(95.0, 100.0, 105.0)),
+        ]);
+        let comparison = Comparison::new(&baseline, &current, 0.05);
+
+        assert!(matches!(comparison.entries()[1].outcome, Outcome::NewInCurrent(_)));
+        assert!(!comparison.fails());
+    }
+
+    #[test]
+    fn table_lists_every_outcome() {
+        let baseline = report(vec![
+            result("regressed", estimate(950.0, 1000.0, 1050.0)),
+            result("missing", estimate(1.5e6, 2e6, 2.5e6)),
+        ]);
+        let current = report(vec![
+            result("regressed", estimate(1950.0, 2000.0, 2050.0)),
+            result("new", estimate(9.0, 10.0, 11.0)),
+        ]);
+        let table = Comparison::new(&baseline, &current, 0.05).to_string();
+
+        let expected = "\
+benchset | target    | baseline | current  | change                       | verdict
+-------------------------------------------------------------------------------------
+bench    | regressed | 1.00 µs  | 2.00 µs  | +100.00% [+85.71%, +115.79%] | REGRESSED
+bench    | missing   | 2.00 ms  | -        |                              | missing
+bench    | new       | -        | 10.00 ns |                              | new
+
+1 of 3 targets regressed by more than 5.00%.
+1 targets of the baseline are missing from the current report.
+";
+        assert_eq!(table, expected);
+    }
+}
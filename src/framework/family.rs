@@ -0,0 +1,57 @@
+//! Benchsets run over every member of a [`DatasetFamily`], reporting throughput as a function of input size.
+use super::{BenchmarkError, Benchset};
+use crate::dataset::DatasetFamily;
+use criterion::Criterion;
+use std::time::SystemTime;
+
+/// The same benchset, configured by `configure`, run for each member of a dataset family.
+///
+/// All members are benchmarked in a single criterion group named `{family}_{id}`, with targets identified
+/// by a [`BenchmarkId`](criterion::BenchmarkId) parameterized by the input size in bytes,
+/// so that criterion plots throughput against input size. Reported targets are named `{target}/{size}`.
+pub struct BenchsetFamily<F> {
+    id: String,
+    family: DatasetFamily,
+    configure: F,
+}
+
+impl<F> BenchsetFamily<F>
+where
+    F: Fn(Benchset) -> Result<Benchset, BenchmarkError>,
+{
+    pub fn new<S: Into<String>>(id: S, family: DatasetFamily, configure: F) -> Self {
+        Self {
+            id: id.into(),
+            family,
+            configure,
+        }
+    }
+
+    pub fn group_id(&self) -> String {
+        format!("{}_{}", self.family.name(), self.id)
+    }
+
+    /// Configure and run members one after another, so that only one dataset is held in memory at a time.
+    pub fn run(&self, c: &mut Criterion) -> Result<(), BenchmarkError> {
+        let started_at = SystemTime::now();
+        let group_id = self.group_id();
+        let mut group = c.benchmark_group(&group_id);
+
+        for dataset in self.family.members() {
+            let mut benchset = (self.configure)(Benchset::new(&self.id, dataset.clone())?)?;
+            if benchset.measure_phases || !benchset.perf_counters.is_empty() {
+                eprintln!("Phases and perf counters are not measured for members of dataset family {group_id}.");
+                benchset.measure_phases = false;
+                benchset.perf_counters.clear();
+            }
+            benchset.id = group_id.clone();
+            benchset.in_family = true;
+
+            benchset.finish()?.run_as_family_member(&mut group, started_at)?;
+        }
+
+        group.finish();
+
+        Ok(())
+    }
+}
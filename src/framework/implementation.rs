@@ -1,4 +1,5 @@
-use std::fmt::Display;
+use super::{verification::ResultSummary, BenchmarkError, ResultType};
+use std::{fmt::Display, fs, time::Duration};
 
 pub trait Implementation: Sized {
     type Query;
@@ -8,22 +9,71 @@ pub trait Implementation: Sized {
 
     fn id() -> &'static str;
 
+    fn result_type() -> ResultType;
+
+    /// Whether the engine's performance depends on the SIMD configuration of rsonpath.
+    fn uses_simd() -> bool {
+        false
+    }
+
+    /// Whether the engine evaluates the query at all. Baselines that only parse the document
+    /// are not checked against other engines.
+    fn evaluates_query() -> bool {
+        true
+    }
+
+    /// Whether the engine measures the time of its runs itself, see [`Implementation::reported_time`].
+    fn reports_time() -> bool {
+        false
+    }
+
+    /// Time the engine measured for producing the result. If present, it is used instead of the wall time
+    /// of [`Implementation::run`] when benchmarking the run phase, e.g. to exclude communication with the engine.
+    fn reported_time(_result: &Self::Result<'_>) -> Option<Duration> {
+        None
+    }
+
     fn new() -> Result<Self, Self::Error>;
 
     fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error>;
 
     fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error>;
 
+    /// Untimed setup run once when the target is prepared, e.g. to size buffers reused between runs.
+    fn warm_up(&self, _file_path: &str, _query: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
     fn run<'a>(&self, query: &'a Self::Query, file: &'a Self::File) -> Result<Self::Result<'a>, Self::Error>;
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error>;
+}
+
+/// Engines that can load a single record of a JSON Lines dataset from memory,
+/// so that the query can be applied to every record separately.
+pub trait LoadRecord: Implementation {
+    fn load_record(&self, record: &str) -> Result<Self::File, Self::Error>;
 }
 
 pub struct PreparedQuery<I: Implementation> {
     pub(crate) implementation: I,
-    pub(crate) id: &'static str,
+    pub(crate) id: String,
+    pub(crate) query_string: String,
+    pub(crate) file_path: String,
     pub(crate) query: Query<I::Query>,
     pub(crate) file: File<I::File>,
 }
 
+/// Query applied to each record of a JSON Lines file.
+pub struct PreparedRecords<I: LoadRecord> {
+    pub(crate) implementation: I,
+    pub(crate) id: String,
+    pub(crate) query_string: String,
+    pub(crate) file_path: String,
+    pub(crate) query: Query<I::Query>,
+    pub(crate) records: File<Vec<I::File>>,
+}
+
 pub(crate) enum File<F> {
     NeedToLoad(String),
     AlreadyLoaded(F),
@@ -54,47 +104,84 @@ impl<Q> Query<Q> {
     }
 }
 
-pub(crate) fn prepare<I: Implementation>(
+pub(crate) fn prepare_with_id<I: Implementation>(
     implementation: I,
+    id: &str,
     file_path: &str,
     query: &str,
     load_ahead_of_time: bool,
     compile_ahead_of_time: bool,
 ) -> Result<PreparedQuery<I>, I::Error> {
-    prepare_with_id(
+    implementation.warm_up(file_path, query)?;
+    let query_string = query.to_string();
+    let query = if compile_ahead_of_time {
+        Query::from_query(implementation.compile_query(query)?)
+    } else {
+        Query::from_str(query)
+    };
+
+    let file = if load_ahead_of_time {
+        File::from_file(implementation.load_file(file_path)?)
+    } else {
+        File::from_path(file_path)
+    };
+
+    Ok(PreparedQuery {
         implementation,
-        I::id(),
-        file_path,
+        id: id.to_string(),
+        query_string,
+        file_path: file_path.to_string(),
         query,
-        load_ahead_of_time,
-        compile_ahead_of_time,
-    )
+        file,
+    })
 }
 
-pub(crate) fn prepare_with_id<I: Implementation>(
+pub(crate) fn prepare_records_with_id<I: LoadRecord>(
     implementation: I,
-    id: &'static str,
+    id: &str,
     file_path: &str,
     query: &str,
     load_ahead_of_time: bool,
     compile_ahead_of_time: bool,
-) -> Result<PreparedQuery<I>, I::Error> {
+) -> Result<PreparedRecords<I>, BenchmarkError>
+where
+    BenchmarkError: From<I::Error>,
+{
+    let query_string = query.to_string();
     let query = if compile_ahead_of_time {
         Query::from_query(implementation.compile_query(query)?)
     } else {
         Query::from_str(query)
     };
 
-    let file = if load_ahead_of_time {
-        File::from_file(implementation.load_file(file_path)?)
+    let records = if load_ahead_of_time {
+        File::from_file(load_records(&implementation, file_path)?)
     } else {
         File::from_path(file_path)
     };
 
-    Ok(PreparedQuery {
+    Ok(PreparedRecords {
         implementation,
-        id,
+        id: id.to_string(),
+        query_string,
+        file_path: file_path.to_string(),
         query,
-        file,
+        records,
     })
 }
+
+/// Read a JSON Lines file and load every non-empty line as a separate record.
+pub(crate) fn load_records<I: LoadRecord>(implementation: &I, file_path: &str) -> Result<Vec<I::File>, BenchmarkError>
+where
+    BenchmarkError: From<I::Error>,
+{
+    let contents = fs::read_to_string(file_path).map_err(BenchmarkError::RecordsError)?;
+
+    Ok(records(&contents)
+        .map(|record| implementation.load_record(record))
+        .collect::<Result<_, _>>()?)
+}
+
+pub(crate) fn records(contents: &str) -> impl Iterator<Item = &str> {
+    contents.lines().filter(|line| !line.trim().is_empty())
+}
@@ -0,0 +1,213 @@
+//! Memory usage measurement of the load, compile and run phases of a target.
+//!
+//! Heap usage is tracked by [`CountingAllocator`], which has to be installed as the global allocator
+//! (the [`benchsets`](crate::benchsets) macro does that when the `memory` feature is enabled).
+//! It only sees allocations made through the Rust allocator, so memory used by the JVM for JSurfer
+//! shows up only in the peak RSS of the process.
+use serde::{Deserialize, Serialize};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Display,
+    fs,
+    sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+};
+
+pub const MEASURE_MEMORY_ENV_VAR: &str = "RSONPATH_BENCH_MEASURE_MEMORY";
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static LIVE_BYTES: AtomicI64 = AtomicI64::new(0);
+static PEAK_LIVE_BYTES: AtomicI64 = AtomicI64::new(0);
+
+pub fn is_enabled() -> bool {
+    std::env::var(MEASURE_MEMORY_ENV_VAR).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Wrapper over the [`System`] allocator counting allocations while a phase is being measured.
+/// Outside of measurement it only adds a single relaxed atomic load per call.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && ACTIVE.load(Ordering::Relaxed) {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && ACTIVE.load(Ordering::Relaxed) {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if ACTIVE.load(Ordering::Relaxed) {
+            LIVE_BYTES.fetch_sub(layout.size() as i64, Ordering::Relaxed);
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && ACTIVE.load(Ordering::Relaxed) {
+            LIVE_BYTES.fetch_sub(layout.size() as i64, Ordering::Relaxed);
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn record_alloc(size: usize) {
+    INSTALLED.store(true, Ordering::Relaxed);
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+    let live = LIVE_BYTES.fetch_add(size as i64, Ordering::Relaxed) + size as i64;
+    PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+}
+
+/// Memory used by a target, measured on a single, separate run outside of criterion's timing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub load: PhaseMemory,
+    pub compile: PhaseMemory,
+    pub run: PhaseMemory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseMemory {
+    /// Number of heap allocations, including reallocations.
+    /// `None` if the counting allocator is not installed.
+    pub allocations: Option<u64>,
+    /// Total number of bytes requested from the allocator.
+    pub allocated_bytes: Option<u64>,
+    /// Highest amount of heap memory held at once during the phase, above what was held when it started.
+    pub peak_heap_bytes: Option<u64>,
+    /// Peak resident set size of the process. On Linux the peak is reset before every phase,
+    /// elsewhere this is the peak over the whole lifetime of the process.
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// Measurement of a single phase. Phases cannot overlap.
+pub(crate) struct Phase {
+    start_live_bytes: i64,
+}
+
+impl Phase {
+    pub(crate) fn start() -> Self {
+        reset_peak_rss();
+        ALLOCATIONS.store(0, Ordering::Relaxed);
+        ALLOCATED_BYTES.store(0, Ordering::Relaxed);
+        let start_live_bytes = LIVE_BYTES.load(Ordering::Relaxed);
+        PEAK_LIVE_BYTES.store(start_live_bytes, Ordering::Relaxed);
+        ACTIVE.store(true, Ordering::SeqCst);
+
+        Self { start_live_bytes }
+    }
+
+    pub(crate) fn finish(self) -> PhaseMemory {
+        ACTIVE.store(false, Ordering::SeqCst);
+        let installed = INSTALLED.load(Ordering::Relaxed);
+        let counted = |value: u64| installed.then_some(value);
+        let peak_heap = PEAK_LIVE_BYTES.load(Ordering::Relaxed) - self.start_live_bytes;
+
+        PhaseMemory {
+            allocations: counted(ALLOCATIONS.load(Ordering::Relaxed)),
+            allocated_bytes: counted(ALLOCATED_BYTES.load(Ordering::Relaxed)),
+            peak_heap_bytes: counted(peak_heap.max(0) as u64),
+            peak_rss_bytes: peak_rss(),
+        }
+    }
+}
+
+/// Reset the peak RSS of the process, as reported by `VmHWM`. Supported on Linux only.
+fn reset_peak_rss() {
+    // Ignore errors, worst case scenario the peak is over the lifetime of the process.
+    fs::write("/proc/self/clear_refs", "5").unwrap_or(());
+}
+
+fn peak_rss() -> Option<u64> {
+    peak_rss_from_procfs().or_else(peak_rss_from_rusage)
+}
+
+fn peak_rss_from_procfs() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kilobytes: u64 = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+
+    Some(kilobytes * 1024)
+}
+
+#[cfg(not(unix))]
+fn peak_rss_from_rusage() -> Option<u64> {
+    None
+}
+
+#[cfg(unix)]
+fn peak_rss_from_rusage() -> Option<u64> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+    let result = unsafe { libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) };
+    if result != 0 {
+        return None;
+    }
+    let max_rss = u64::try_from(unsafe { usage.assume_init() }.ru_maxrss).ok()?;
+
+    // Linux reports kilobytes, macOS reports bytes.
+    if cfg!(target_os = "macos") {
+        Some(max_rss)
+    } else {
+        Some(max_rss * 1024)
+    }
+}
+
+impl Display for MemoryUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "load: {}; compile: {}; run: {}", self.load, self.compile, self.run)
+    }
+}
+
+impl Display for PhaseMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.allocations, self.allocated_bytes, self.peak_heap_bytes) {
+            (Some(allocations), Some(allocated), Some(peak)) => write!(
+                f,
+                "{allocations} allocs, {} allocated, {} peak heap",
+                format_bytes(allocated),
+                format_bytes(peak)
+            )?,
+            _ => write!(f, "heap not measured")?,
+        }
+        if let Some(rss) = self.peak_rss_bytes {
+            write!(f, ", {} peak RSS", format_bytes(rss))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
@@ -0,0 +1,290 @@
+//! Criterion measurement counting hardware events with Linux `perf_event_open`.
+//!
+//! Counting requires the `perf` feature, Linux, and a permissive enough `perf_event_paranoid` setting.
+//! When counters are unavailable, e.g. in most containers, [`PerfMeasurement`] falls back to wall time.
+use criterion::{
+    measurement::{Measurement, ValueFormatter, WallTime},
+    Throughput,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr, time::Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PerfCounter {
+    Cycles,
+    Instructions,
+    BranchMisses,
+    CacheMisses,
+}
+
+impl PerfCounter {
+    pub const ALL: [PerfCounter; 4] = [Self::Cycles, Self::Instructions, Self::BranchMisses, Self::CacheMisses];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cycles => "cycles",
+            Self::Instructions => "instructions",
+            Self::BranchMisses => "branch_misses",
+            Self::CacheMisses => "cache_misses",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            Self::Cycles => "cycles",
+            Self::Instructions => "instr",
+            Self::BranchMisses => "br-miss",
+            Self::CacheMisses => "cache-miss",
+        }
+    }
+
+    fn unit_per_byte(&self) -> &'static str {
+        match self {
+            Self::Cycles => "cycles/B",
+            Self::Instructions => "instr/B",
+            Self::BranchMisses => "br-miss/B",
+            Self::CacheMisses => "cache-miss/B",
+        }
+    }
+
+    fn unit_per_element(&self) -> &'static str {
+        match self {
+            Self::Cycles => "cycles/elem",
+            Self::Instructions => "instr/elem",
+            Self::BranchMisses => "br-miss/elem",
+            Self::CacheMisses => "cache-miss/elem",
+        }
+    }
+}
+
+impl Display for PerfCounter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for PerfCounter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.name() == s)
+            .ok_or_else(|| format!("unknown perf counter: {s}"))
+    }
+}
+
+/// Measures the number of events of a [`PerfCounter`] per iteration,
+/// or wall time if the counter could not be opened.
+///
+/// With [`Throughput::Bytes`] set on the group, criterion reports the throughput as events per input byte.
+pub struct PerfMeasurement {
+    counter: PerfCounter,
+    backend: Backend,
+    #[cfg_attr(not(all(feature = "perf", target_os = "linux")), allow(dead_code))]
+    formatter: CounterFormatter,
+}
+
+enum Backend {
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    Counter(sys::CounterFd),
+    WallTime(WallTime),
+}
+
+pub enum PerfIntermediate {
+    Count(u64),
+    Instant(Instant),
+}
+
+impl PerfMeasurement {
+    pub fn new(counter: PerfCounter) -> Self {
+        Self {
+            counter,
+            backend: Backend::open(counter),
+            formatter: CounterFormatter(counter),
+        }
+    }
+
+    pub fn counter(&self) -> PerfCounter {
+        self.counter
+    }
+
+    /// Whether hardware events are counted, as opposed to falling back to wall time.
+    pub fn is_counting(&self) -> bool {
+        !matches!(self.backend, Backend::WallTime(_))
+    }
+}
+
+impl Backend {
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    fn open(counter: PerfCounter) -> Self {
+        match sys::CounterFd::open(counter) {
+            Ok(fd) => Self::Counter(fd),
+            Err(err) => {
+                eprintln!("Cannot open perf counter {counter} ({err}), falling back to wall time.");
+                Self::WallTime(WallTime)
+            }
+        }
+    }
+
+    #[cfg(not(all(feature = "perf", target_os = "linux")))]
+    fn open(counter: PerfCounter) -> Self {
+        eprintln!("Perf counter {counter} requires the `perf` feature on Linux, falling back to wall time.");
+        Self::WallTime(WallTime)
+    }
+}
+
+impl Measurement for PerfMeasurement {
+    type Intermediate = PerfIntermediate;
+
+    type Value = f64;
+
+    fn start(&self) -> Self::Intermediate {
+        match &self.backend {
+            #[cfg(all(feature = "perf", target_os = "linux"))]
+            Backend::Counter(fd) => PerfIntermediate::Count(fd.read()),
+            Backend::WallTime(_) => PerfIntermediate::Instant(Instant::now()),
+        }
+    }
+
+    fn end(&self, i: Self::Intermediate) -> Self::Value {
+        match (&self.backend, i) {
+            #[cfg(all(feature = "perf", target_os = "linux"))]
+            (Backend::Counter(fd), PerfIntermediate::Count(start)) => fd.read().saturating_sub(start) as f64,
+            (_, PerfIntermediate::Instant(start)) => start.elapsed().as_nanos() as f64,
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("measurement started with a different backend"),
+        }
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0.0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        match &self.backend {
+            #[cfg(all(feature = "perf", target_os = "linux"))]
+            Backend::Counter(_) => &self.formatter,
+            Backend::WallTime(wall_time) => wall_time.formatter(),
+        }
+    }
+}
+
+struct CounterFormatter(PerfCounter);
+
+impl ValueFormatter for CounterFormatter {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        self.0.unit()
+    }
+
+    fn scale_throughputs(&self, _typical_value: f64, throughput: &Throughput, values: &mut [f64]) -> &'static str {
+        let (denominator, unit) = match *throughput {
+            Throughput::Bytes(n) | Throughput::BytesDecimal(n) => (n, self.0.unit_per_byte()),
+            Throughput::Elements(n) => (n, self.0.unit_per_element()),
+        };
+        for value in values {
+            *value /= denominator as f64;
+        }
+
+        unit
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        self.0.unit()
+    }
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+mod sys {
+    use super::PerfCounter;
+    use std::io;
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    /// The first published version of `struct perf_event_attr` (`PERF_ATTR_SIZE_VER0`),
+    /// which is all that is needed to count a single hardware event.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        type_: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    pub(super) struct CounterFd(libc::c_int);
+
+    impl CounterFd {
+        /// Open an enabled counter for the calling thread, counting user-space events only.
+        pub(super) fn open(counter: PerfCounter) -> io::Result<Self> {
+            let config = match counter {
+                PerfCounter::Cycles => PERF_COUNT_HW_CPU_CYCLES,
+                PerfCounter::Instructions => PERF_COUNT_HW_INSTRUCTIONS,
+                PerfCounter::BranchMisses => PERF_COUNT_HW_BRANCH_MISSES,
+                PerfCounter::CacheMisses => PERF_COUNT_HW_CACHE_MISSES,
+            };
+            let attr = PerfEventAttr {
+                type_: PERF_TYPE_HARDWARE,
+                size: std::mem::size_of::<PerfEventAttr>() as u32,
+                config,
+                flags: FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV,
+                ..Default::default()
+            };
+
+            let fd = unsafe {
+                libc::syscall(
+                    libc::SYS_perf_event_open,
+                    &attr as *const PerfEventAttr,
+                    0 as libc::pid_t,
+                    -1 as libc::c_int,
+                    -1 as libc::c_int,
+                    0 as libc::c_ulong,
+                )
+            };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self(fd as libc::c_int))
+        }
+
+        pub(super) fn read(&self) -> u64 {
+            let mut value: u64 = 0;
+            let size = std::mem::size_of::<u64>();
+            let read = unsafe { libc::read(self.0, &mut value as *mut u64 as *mut libc::c_void, size) };
+            if read != size as isize {
+                panic!("failed to read perf counter: {}", io::Error::last_os_error());
+            }
+
+            value
+        }
+    }
+
+    impl Drop for CounterFd {
+        fn drop(&mut self) {
+            unsafe { libc::close(self.0) };
+        }
+    }
+}
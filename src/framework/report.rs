@@ -0,0 +1,273 @@
+use super::{memory::MemoryUsage, perf::PerfCounter, BenchPhase, ResultType};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+use thiserror::Error;
+
+pub const REPORT_PATH_ENV_VAR: &str = "RSONPATH_BENCH_REPORT_PATH";
+
+lazy_static! {
+    static ref COLLECTED: Mutex<Vec<TargetResult>> = Mutex::new(vec![]);
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Report {
+    pub results: Vec<TargetResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetResult {
+    pub benchset: String,
+    pub dataset: DatasetInfo,
+    pub target: String,
+    pub engine: String,
+    pub query: String,
+    pub result_type: ResultType,
+    /// Set if this result times only a single phase of the target.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<BenchPhase>,
+    /// Set if the estimates are counts of a hardware event instead of nanoseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counter: Option<PerfCounter>,
+    /// SIMD configuration of rsonpath the target was run with, for targets that depend on it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simd: Option<String>,
+    pub measure_file_load: bool,
+    pub measure_compilation_time: bool,
+    pub throughput_bytes: u64,
+    /// Number of records queried in each iteration, for targets querying each record of a JSON Lines dataset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput_records: Option<u64>,
+    pub mean: Estimate,
+    pub median: Estimate,
+    /// Present only if memory was measured, see [`MEASURE_MEMORY_ENV_VAR`](super::memory::MEASURE_MEMORY_ENV_VAR).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetInfo {
+    pub name: String,
+    pub path: String,
+    pub size_in_bytes: usize,
+    pub checksum: String,
+}
+
+/// Time estimates in nanoseconds per iteration, as computed by criterion.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Estimate {
+    pub point_estimate: f64,
+    pub standard_error: f64,
+    pub confidence_interval: ConfidenceInterval,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub confidence_level: f64,
+    pub lower_bound: f64,
+    pub upper_bound: f64,
+}
+
+#[derive(Deserialize)]
+struct CriterionEstimates {
+    mean: Estimate,
+    median: Estimate,
+}
+
+impl Report {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ReportError> {
+        let file = fs::File::open(path).map_err(ReportError::FileSystemError)?;
+        serde_json::from_reader(io::BufReader::new(file)).map_err(ReportError::SerializationError)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ReportError> {
+        let file = fs::File::create(path).map_err(ReportError::FileSystemError)?;
+        serde_json::to_writer_pretty(io::BufWriter::new(file), self).map_err(ReportError::SerializationError)
+    }
+
+    /// Add results, replacing any previous results for the same target in the same benchset.
+    pub fn merge<I: IntoIterator<Item = TargetResult>>(&mut self, results: I) {
+        for result in results {
+            self.results
+                .retain(|r| r.benchset != result.benchset || r.target != result.target);
+            self.results.push(result);
+        }
+    }
+}
+
+pub fn report_path() -> Option<PathBuf> {
+    std::env::var_os(REPORT_PATH_ENV_VAR).map(PathBuf::from)
+}
+
+pub(crate) fn collect(result: TargetResult) {
+    COLLECTED.lock().unwrap().push(result);
+}
+
+/// Write all results collected in this process to the path in [`REPORT_PATH_ENV_VAR`], if set.
+///
+/// Results already present in the file are kept, unless they are for the same target of the same benchset,
+/// so that all bench binaries of a single `cargo bench` can share one report.
+pub fn write_collected() -> Result<(), ReportError> {
+    let Some(path) = report_path() else {
+        return Ok(());
+    };
+    let mut report = match Report::load(&path) {
+        Ok(report) => report,
+        Err(ReportError::FileSystemError(err)) if err.kind() == io::ErrorKind::NotFound => Report::default(),
+        Err(err) => return Err(err),
+    };
+    let collected = std::mem::take(&mut *COLLECTED.lock().unwrap());
+    report.merge(collected);
+
+    report.save(path)
+}
+
+/// Read the estimates criterion saved for a benchmark, if they were produced after `since`.
+///
+/// Criterion does not expose its results programmatically, so this mirrors the layout it uses under its
+/// output directory, reading the baseline given with `--save-baseline` if any. Benchmarks skipped in this run (e.g. by a filter) leave stale or no files, and are ignored,
+/// with a warning if there is no file at all.
+pub(crate) fn read_criterion_estimates(
+    group_id: &str,
+    function_id: &str,
+    parameter: Option<&str>,
+    since: SystemTime,
+) -> Result<Option<(Estimate, Estimate)>, ReportError> {
+    let mut path = criterion_output_directory()
+        .join(make_filename_safe(group_id))
+        .join(make_filename_safe(function_id));
+    if let Some(parameter) = parameter {
+        path.push(make_filename_safe(parameter));
+    }
+    let path = path.join(criterion_baseline()).join("estimates.json");
+
+    let modified = match fs::metadata(&path).and_then(|m| m.modified()) {
+        Ok(modified) => modified,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            eprintln!(
+                "Criterion estimates not found at {}, leaving the target out of the report.",
+                path.display()
+            );
+            return Ok(None);
+        }
+        Err(err) => return Err(ReportError::FileSystemError(err)),
+    };
+    if modified < since {
+        return Ok(None);
+    }
+
+    let file = fs::File::open(path).map_err(ReportError::FileSystemError)?;
+    let estimates: CriterionEstimates =
+        serde_json::from_reader(io::BufReader::new(file)).map_err(ReportError::SerializationError)?;
+
+    Ok(Some((estimates.mean, estimates.median)))
+}
+
+/// First pair of target ids criterion would store in the same directory. Criterion silently renames
+/// the second one with a numeric suffix, so its estimates could not be found.
+pub(crate) fn find_directory_collision<'a>(ids: impl IntoIterator<Item = &'a str>) -> Option<(&'a str, &'a str)> {
+    let mut seen: HashMap<String, &str> = HashMap::new();
+
+    for id in ids {
+        if let Some(first) = seen.insert(make_filename_safe(id), id) {
+            return Some((first, id));
+        }
+    }
+
+    None
+}
+
+/// Name of the directory criterion saves the estimates of this run in.
+fn criterion_baseline() -> String {
+    baseline_from_args(std::env::args()).unwrap_or_else(|| "new".to_owned())
+}
+
+fn baseline_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--save-baseline" || arg == "-s" {
+            return args.next();
+        }
+        if let Some(name) = arg.strip_prefix("--save-baseline=") {
+            return Some(name.to_owned());
+        }
+    }
+
+    None
+}
+
+fn criterion_output_directory() -> PathBuf {
+    if let Some(home) = std::env::var_os("CRITERION_HOME") {
+        PathBuf::from(home)
+    } else if let Some(target) = std::env::var_os("CARGO_TARGET_DIR") {
+        PathBuf::from(target).join("criterion")
+    } else {
+        PathBuf::from("target/criterion")
+    }
+}
+
+fn make_filename_safe(string: &str) -> String {
+    const MAX_DIRECTORY_NAME_LEN: usize = 64;
+    let mut string = string.replace(&['?', '"', '/', '\\', '*', '<', '>', ':', '|', '^'][..], "_");
+
+    if string.len() > MAX_DIRECTORY_NAME_LEN {
+        let mut boundary = MAX_DIRECTORY_NAME_LEN;
+        while !string.is_char_boundary(boundary) {
+            boundary -= 1;
+        }
+        string.truncate(boundary);
+    }
+
+    if cfg!(target_os = "windows") {
+        string = string.trim_end().to_lowercase();
+    }
+
+    string
+}
+
+#[derive(Error, Debug)]
+pub enum ReportError {
+    #[error("Filesystem error: {0}")]
+    FileSystemError(#[source] io::Error),
+    #[error("error (de)serializing the report: {0}")]
+    SerializationError(#[source] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|&a| a.to_owned()).collect()
+    }
+
+    #[test]
+    fn baseline_is_read_from_save_baseline() {
+        assert_eq!(baseline_from_args(args(&["bench", "--bench"])), None);
+        assert_eq!(
+            baseline_from_args(args(&["bench", "--save-baseline", "main"])).as_deref(),
+            Some("main")
+        );
+        assert_eq!(
+            baseline_from_args(args(&["bench", "--save-baseline=main"])).as_deref(),
+            Some("main")
+        );
+        assert_eq!(
+            baseline_from_args(args(&["bench", "-s", "main"])).as_deref(),
+            Some("main")
+        );
+    }
+
+    #[test]
+    fn ids_mapped_to_the_same_directory_collide() {
+        assert_eq!(find_directory_collision(["rsonpath", "jaq"]), None);
+        assert_eq!(find_directory_collision(["a/b", "jaq", "a:b"]), Some(("a/b", "a:b")));
+    }
+}
@@ -0,0 +1,94 @@
+//! SIMD configurations of rsonpath that can be benchmarked side by side.
+//!
+//! rsonpath picks its SIMD configuration when a query is compiled, either by detecting CPU features
+//! or from the [`FORCE_SIMD_ENV_VAR`] override, so targets are compiled and run with the variable set.
+use std::env;
+
+pub const FORCE_SIMD_ENV_VAR: &str = "RSONPATH_UNSAFE_FORCE_SIMD";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimdConfig {
+    name: &'static str,
+    force: String,
+}
+
+impl SimdConfig {
+    fn new(name: &'static str, fast_quotes: bool, fast_popcnt: bool) -> Self {
+        let quotes = if fast_quotes { "fast_quotes" } else { "slow_quotes" };
+        let popcnt = if fast_popcnt { "fast_popcnt" } else { "slow_popcnt" };
+
+        Self {
+            name,
+            force: format!("{name};{quotes};{popcnt}"),
+        }
+    }
+
+    /// Short name of the SIMD level, used as a suffix of target ids.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Full configuration, as passed to rsonpath in [`FORCE_SIMD_ENV_VAR`].
+    pub fn force_value(&self) -> &str {
+        &self.force
+    }
+}
+
+/// The configuration rsonpath uses for queries compiled right now, as detected or forced.
+pub fn current() -> String {
+    rsonpath::classification::simd::configure().to_string()
+}
+
+/// Id of a target run with the given SIMD level, e.g. `rsonpath_mmap[avx2]`.
+pub(crate) fn target_id(id: &str, level: &str) -> String {
+    format!("{id}[{level}]")
+}
+
+/// SIMD level of [`current`], e.g. `avx2`.
+pub fn current_level() -> String {
+    let current = current();
+    current.split(';').next().unwrap_or(&current).to_owned()
+}
+
+/// All configurations supported by this CPU, from no SIMD at all up to the highest level.
+///
+/// Without the `simd` feature rsonpath never uses SIMD, so only `nosimd` is available.
+pub fn available() -> Vec<SimdConfig> {
+    #[allow(unused_mut)]
+    let mut configs = vec![SimdConfig::new("nosimd", false, false)];
+
+    #[cfg(all(feature = "simd", any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        let fast_quotes = is_x86_feature_detected!("pclmulqdq");
+        let fast_popcnt = is_x86_feature_detected!("popcnt");
+
+        if is_x86_feature_detected!("sse2") {
+            configs.push(SimdConfig::new("sse2", fast_quotes, fast_popcnt));
+        }
+        if is_x86_feature_detected!("ssse3") {
+            configs.push(SimdConfig::new("ssse3", fast_quotes, fast_popcnt));
+        }
+        if is_x86_feature_detected!("avx2") {
+            configs.push(SimdConfig::new("avx2", fast_quotes, fast_popcnt));
+        }
+    }
+
+    configs
+}
+
+/// Run `f` with rsonpath forced to use `config`, or with the current configuration if `None`.
+pub(crate) fn with_forced<T, F: FnOnce() -> T>(config: Option<&SimdConfig>, f: F) -> T {
+    let Some(config) = config else {
+        return f();
+    };
+
+    let previous = env::var_os(FORCE_SIMD_ENV_VAR);
+    env::set_var(FORCE_SIMD_ENV_VAR, config.force_value());
+    let result = f();
+    match previous {
+        Some(value) => env::set_var(FORCE_SIMD_ENV_VAR, value),
+        None => env::remove_var(FORCE_SIMD_ENV_VAR),
+    }
+
+    result
+}
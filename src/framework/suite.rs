@@ -0,0 +1,374 @@
+use super::{perf::PerfCounter, BenchTarget, BenchmarkError, Benchset, ConfiguredBenchset, ResultType};
+use crate::{
+    dataset::{self, Compression},
+    implementations::{external::ExternalCommand, rsonpath::MatchCollection},
+};
+use criterion::Criterion;
+use serde::Deserialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+pub const SUITE_PATH_ENV_VAR: &str = "RSONPATH_BENCH_SUITE";
+
+/// A list of benchsets read from a suite file.
+///
+/// Supported formats are TOML (`.toml`) and JSON (`.json`), both with a top-level `benchset` array,
+/// and JSON Lines (`.jsonl`) with one benchset object per line.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Suite {
+    #[serde(rename = "benchset", default)]
+    pub benchsets: Vec<BenchsetSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenchsetSpec {
+    pub id: String,
+    pub dataset: String,
+    /// Query used for all targets that do not specify their own.
+    pub query: Option<String>,
+    #[serde(default = "default_true")]
+    pub measure_file_load: bool,
+    #[serde(default)]
+    pub measure_compilation_time: bool,
+    #[serde(default)]
+    pub measure_phases: bool,
+    #[serde(default)]
+    pub perf_counters: Vec<PerfCounter>,
+    #[serde(default)]
+    pub compare_simd: bool,
+    /// Apply the query to each record of a JSON Lines dataset.
+    #[serde(default)]
+    pub query_each_record: bool,
+    #[serde(default = "default_true")]
+    pub verify_results: bool,
+    pub targets: Vec<TargetSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetSpec {
+    pub engine: TargetEngine,
+    pub id: Option<String>,
+    pub query: Option<String>,
+    pub result_type: Option<ResultType>,
+    /// What full rsonpath targets do with matches, see [`MatchCollection`].
+    pub sink: Option<MatchCollection>,
+    /// Format of the compressed copy read by `rsonpath_compressed` targets, gzip by default.
+    pub compression: Option<Compression>,
+    /// Program and arguments of `external` targets.
+    pub command: Option<ExternalCommand>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetEngine {
+    Rsonpath,
+    RsonpathMmap,
+    RsonpathBuffered,
+    RsonpathCompressed,
+    Jsurfer,
+    JsonpathRust,
+    SerdeJsonPath,
+    JsonpathLib,
+    Jaq,
+    Jsonski,
+    SerdeJsonValue,
+    SerdeJsonIgnoredAny,
+    External,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Suite {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SuiteError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(SuiteError::FileSystemError)?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml(&contents),
+            Some("json") => Self::from_json(&contents),
+            Some("jsonl") => Self::from_json_lines(&contents),
+            _ => Err(SuiteError::UnknownFormat(path.to_owned())),
+        }
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, SuiteError> {
+        toml::from_str(contents).map_err(SuiteError::TomlError)
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, SuiteError> {
+        serde_json::from_str(contents).map_err(|err| SuiteError::JsonError(None, err))
+    }
+
+    pub fn from_json_lines(contents: &str) -> Result<Self, SuiteError> {
+        let benchsets = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| serde_json::from_str(line).map_err(|err| SuiteError::JsonError(Some(i + 1), err)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { benchsets })
+    }
+
+    /// Configure and run all benchsets one after another.
+    ///
+    /// Benchsets are configured lazily, so that only one dataset is held in memory at a time.
+    pub fn run(&self, c: &mut Criterion) -> Result<(), BenchmarkError> {
+        for spec in &self.benchsets {
+            spec.configure()?.run(c)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BenchsetSpec {
+    pub fn configure(&self) -> Result<ConfiguredBenchset, BenchmarkError> {
+        let dataset = dataset::by_name(&self.dataset)?;
+        let mut benchset = Benchset::new(&self.id, dataset)?;
+
+        if !self.measure_file_load {
+            benchset = benchset.do_not_measure_file_load_time();
+        }
+        if self.measure_compilation_time {
+            benchset = benchset.measure_compilation_time();
+        }
+        if self.measure_phases {
+            benchset = benchset.measure_phases_separately();
+        }
+        if !self.perf_counters.is_empty() {
+            benchset = benchset.measure_perf_counters(&self.perf_counters);
+        }
+        if self.compare_simd {
+            benchset = benchset.compare_simd_configurations();
+        }
+        if self.query_each_record {
+            benchset = benchset.query_each_record()?;
+        }
+        if !self.verify_results {
+            benchset = benchset.do_not_verify_results();
+        }
+
+        for target in &self.targets {
+            let query = target
+                .query
+                .as_deref()
+                .or(self.query.as_deref())
+                .ok_or_else(|| SuiteError::MissingQuery(self.id.clone(), target.engine))?;
+            let bench_target = target.to_bench_target(&self.id, query)?;
+
+            benchset = match &target.id {
+                Some(id) => benchset.add_target_with_id(bench_target, id)?,
+                None => benchset.add_target(bench_target)?,
+            };
+        }
+
+        benchset.finish()
+    }
+}
+
+impl TargetSpec {
+    fn to_bench_target<'q>(&'q self, benchset: &str, query: &'q str) -> Result<BenchTarget<'q>, SuiteError> {
+        if self.compression.is_some() && self.engine != TargetEngine::RsonpathCompressed {
+            return Err(SuiteError::UnsupportedCompression(benchset.to_owned(), self.engine));
+        }
+        if self.command.is_some() && self.engine != TargetEngine::External {
+            return Err(SuiteError::UnsupportedCommand(benchset.to_owned(), self.engine));
+        }
+        if let Some(sink) = self.sink {
+            return match (self.engine, self.result_type) {
+                (TargetEngine::Rsonpath, None | Some(ResultType::Full)) => {
+                    Ok(BenchTarget::RsonpathWithSink(query, sink))
+                }
+                (TargetEngine::RsonpathMmap, None | Some(ResultType::Full)) => {
+                    Ok(BenchTarget::RsonpathMmapWithSink(query, sink))
+                }
+                (TargetEngine::RsonpathBuffered, None | Some(ResultType::Full)) => {
+                    Ok(BenchTarget::RsonpathBufferedWithSink(query, sink))
+                }
+                (engine, _) => Err(SuiteError::UnsupportedSink(benchset.to_owned(), engine)),
+            };
+        }
+
+        match (self.engine, self.result_type) {
+            (TargetEngine::Rsonpath, result_type) => {
+                Ok(BenchTarget::Rsonpath(query, result_type.unwrap_or(ResultType::Full)))
+            }
+            (TargetEngine::RsonpathMmap, result_type) => Ok(BenchTarget::RsonpathMmap(
+                query,
+                result_type.unwrap_or(ResultType::Full),
+            )),
+            (TargetEngine::RsonpathBuffered, result_type) => Ok(BenchTarget::RsonpathBuffered(
+                query,
+                result_type.unwrap_or(ResultType::Full),
+            )),
+            (TargetEngine::RsonpathCompressed, result_type @ (None | Some(ResultType::Full | ResultType::Count))) => {
+                Ok(BenchTarget::RsonpathCompressed(
+                    query,
+                    self.compression.unwrap_or(Compression::Gzip),
+                    result_type.unwrap_or(ResultType::Full),
+                ))
+            }
+            #[cfg(feature = "jsurfer")]
+            (TargetEngine::Jsurfer, None | Some(ResultType::Count)) => Ok(BenchTarget::JSurfer(query)),
+            #[cfg(not(feature = "jsurfer"))]
+            (TargetEngine::Jsurfer, _) => Err(SuiteError::JsurferNotEnabled(benchset.to_owned())),
+            (TargetEngine::JsonpathRust, None | Some(ResultType::Full)) => Ok(BenchTarget::JsonpathRust(query)),
+            (TargetEngine::SerdeJsonPath, None | Some(ResultType::Full)) => Ok(BenchTarget::SerdeJsonPath(query)),
+            #[cfg(feature = "jsonpath_lib")]
+            (TargetEngine::JsonpathLib, None | Some(ResultType::Full)) => Ok(BenchTarget::JsonpathLib(query)),
+            #[cfg(not(feature = "jsonpath_lib"))]
+            (TargetEngine::JsonpathLib, _) => Err(SuiteError::JsonpathLibNotEnabled(benchset.to_owned())),
+            (TargetEngine::Jaq, None | Some(ResultType::Full)) => Ok(BenchTarget::Jaq(query)),
+            #[cfg(feature = "jsonski")]
+            (TargetEngine::Jsonski, None | Some(ResultType::Count)) => Ok(BenchTarget::JsonSki(query)),
+            #[cfg(not(feature = "jsonski"))]
+            (TargetEngine::Jsonski, _) => Err(SuiteError::JsonskiNotEnabled(benchset.to_owned())),
+            (TargetEngine::SerdeJsonValue, None | Some(ResultType::Count)) => Ok(BenchTarget::SerdeJsonValue(query)),
+            (TargetEngine::SerdeJsonIgnoredAny, None | Some(ResultType::Count)) => {
+                Ok(BenchTarget::SerdeJsonIgnoredAny(query))
+            }
+            (TargetEngine::External, None | Some(ResultType::Count)) => match &self.command {
+                Some(command) => Ok(BenchTarget::External(query, command)),
+                None => Err(SuiteError::MissingCommand(benchset.to_owned())),
+            },
+            (engine, Some(result_type)) => Err(SuiteError::UnsupportedResultType(
+                benchset.to_owned(),
+                engine,
+                result_type,
+            )),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SuiteError {
+    #[error("Filesystem error: {0}")]
+    FileSystemError(#[source] std::io::Error),
+    #[error("unknown suite file format of {0}, expected a .toml, .json, or .jsonl file")]
+    UnknownFormat(PathBuf),
+    #[error("error parsing TOML suite: {0}")]
+    TomlError(#[source] toml::de::Error),
+    #[error("error parsing JSON suite{}: {1}", .0.map(|l| format!(" at line {l}")).unwrap_or_default())]
+    JsonError(Option<usize>, #[source] serde_json::Error),
+    #[error("target {1:?} in benchset {0} has no query and the benchset does not specify a default")]
+    MissingQuery(String, TargetEngine),
+    #[error("target {1:?} in benchset {0} does not support result type {2:?}")]
+    UnsupportedResultType(String, TargetEngine, ResultType),
+    #[error("target {1:?} in benchset {0} does not support choosing a sink, only full rsonpath targets do")]
+    UnsupportedSink(String, TargetEngine),
+    #[error(
+        "target {1:?} in benchset {0} does not support choosing a compression, only rsonpath_compressed targets do"
+    )]
+    UnsupportedCompression(String, TargetEngine),
+    #[error("JSurfer target in benchset {0} requires the `jsurfer` feature")]
+    JsurferNotEnabled(String),
+    #[error("JSONSki target in benchset {0} requires the `jsonski` feature")]
+    JsonskiNotEnabled(String),
+    #[error("jsonpath_lib target in benchset {0} requires the `jsonpath_lib` feature")]
+    JsonpathLibNotEnabled(String),
+    #[error("external target in benchset {0} has no command to run")]
+    MissingCommand(String),
+    #[error("target {1:?} in benchset {0} does not support a command, only external targets do")]
+    UnsupportedCommand(String, TargetEngine),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINIMAL_BENCHSET: &str = r#"
+[[benchset]]
+id = "small::tenants"
+dataset = "az_tenants"
+query = "$..tenantId"
+targets = [{ engine = "rsonpath_mmap" }]
+"#;
+
+    #[test]
+    fn rust_native_suite_is_valid() {
+        let suite = Suite::load(concat!(env!("CARGO_MANIFEST_DIR"), "/suites/rust_native.toml")).unwrap();
+
+        assert!(!suite.benchsets.is_empty());
+        for spec in &suite.benchsets {
+            for target in &spec.targets {
+                let query = target.query.as_deref().or(spec.query.as_deref()).unwrap();
+                target.to_bench_target(&spec.id, query).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn minimal_benchset_uses_defaults() {
+        let suite = Suite::from_toml(MINIMAL_BENCHSET).unwrap();
+
+        let spec = &suite.benchsets[0];
+        assert!(spec.measure_file_load);
+        assert!(spec.verify_results);
+        assert!(!spec.measure_compilation_time);
+        assert_eq!(spec.targets[0].engine, TargetEngine::RsonpathMmap);
+    }
+
+    #[test]
+    fn unknown_benchset_field_is_rejected() {
+        let contents = format!("{MINIMAL_BENCHSET}measure_everything = true\n");
+
+        let result = Suite::from_toml(&contents);
+
+        assert!(matches!(result, Err(SuiteError::TomlError(_))));
+    }
+
+    #[test]
+    fn unknown_target_field_is_rejected() {
+        let contents = MINIMAL_BENCHSET.replace(
+            r#"engine = "rsonpath_mmap""#,
+            r#"engine = "rsonpath_mmap", simd = "avx2""#,
+        );
+
+        let result = Suite::from_toml(&contents);
+
+        assert!(matches!(result, Err(SuiteError::TomlError(_))));
+    }
+
+    #[test]
+    fn unknown_engine_is_rejected() {
+        let contents = MINIMAL_BENCHSET.replace("rsonpath_mmap", "simdjson");
+
+        let result = Suite::from_toml(&contents);
+
+        assert!(matches!(result, Err(SuiteError::TomlError(_))));
+    }
+
+    #[test]
+    fn json_lines_hold_one_benchset_per_line() {
+        let contents = r#"{"id": "a", "dataset": "az_tenants", "query": "$..tenantId", "targets": [{"engine": "rsonpath"}]}
+
+{"id": "b", "dataset": "twitter", "targets": [{"engine": "jaq", "query": "$..count"}]}
+"#;
+
+        let suite = Suite::from_json_lines(contents).unwrap();
+
+        let ids: Vec<_> = suite.benchsets.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(suite.benchsets[1].targets[0].engine, TargetEngine::Jaq);
+    }
+
+    #[test]
+    fn json_lines_errors_report_the_line() {
+        let contents = r#"{"id": "a", "dataset": "az_tenants", "query": "$..tenantId", "targets": []}
+{"id": "b", "dataset": "twitter", "targets": [{"engine": "simdjson"}]}
+"#;
+
+        let result = Suite::from_json_lines(contents);
+
+        assert!(matches!(result, Err(SuiteError::JsonError(Some(2), _))));
+    }
+}
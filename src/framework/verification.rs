@@ -0,0 +1,248 @@
+use serde_json::Value;
+use std::fmt::Display;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultSummary {
+    count: u64,
+    values: Option<Vec<String>>,
+}
+
+impl ResultSummary {
+    pub fn from_count(count: u64) -> Self {
+        Self { count, values: None }
+    }
+
+    pub fn from_values<'a, I: IntoIterator<Item = &'a Value>>(values: I) -> Self {
+        let mut values: Vec<_> = values.into_iter().map(Value::to_string).collect();
+        values.sort_unstable();
+
+        Self {
+            count: values.len() as u64,
+            values: Some(values),
+        }
+    }
+
+    /// Summary of matches given as JSON text. Matches nested deeper than `serde_json` can parse
+    /// are only counted, and the summary has no values then.
+    pub fn from_json_bytes<'a, I: IntoIterator<Item = &'a [u8]>>(matches: I) -> Result<Self, serde_json::Error> {
+        let mut values = vec![];
+        let mut count = 0;
+        let mut too_deep = false;
+
+        for bytes in matches {
+            count += 1;
+            if too_deep {
+                continue;
+            }
+            match serde_json::from_slice::<Value>(bytes) {
+                Ok(value) => values.push(value),
+                Err(err) if is_recursion_limit(&err) => too_deep = true,
+                Err(err) => return Err(err),
+            }
+        }
+
+        if too_deep {
+            Ok(Self::from_count(count))
+        } else {
+            Ok(Self::from_values(&values))
+        }
+    }
+
+    /// Summary of matches from many documents, e.g. from every record of a JSON Lines file.
+    /// Values are kept only if all summaries have them.
+    pub fn combine<I: IntoIterator<Item = ResultSummary>>(summaries: I) -> Self {
+        let mut count = 0;
+        let mut values = Some(vec![]);
+
+        for summary in summaries {
+            count += summary.count;
+            values = values.zip(summary.values).map(|(mut all, new)| {
+                all.extend(new);
+                all
+            });
+        }
+        if let Some(values) = values.as_mut() {
+            values.sort_unstable();
+        }
+
+        Self { count, values }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn values(&self) -> Option<&[String]> {
+        self.values.as_deref()
+    }
+}
+
+/// `serde_json` does not expose the kind of syntax errors, the recursion limit is recognized by its message.
+fn is_recursion_limit(err: &serde_json::Error) -> bool {
+    err.is_syntax() && err.to_string().starts_with("recursion limit exceeded")
+}
+
+#[derive(Error, Debug)]
+pub enum ResultMismatch {
+    #[error("{reference} returned {expected} matches, but {target} returned {actual}")]
+    Count {
+        reference: String,
+        target: String,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("{reference} and {target} returned different values, first difference at match {index}: {} vs {}", DisplayValue(.expected), DisplayValue(.actual))]
+    Values {
+        reference: String,
+        target: String,
+        index: usize,
+        expected: Option<String>,
+        actual: Option<String>,
+    },
+}
+
+#[derive(Error, Debug)]
+#[error("engines disagree on the result of '{query}' in benchset {benchset}: {mismatch}")]
+pub struct ResultMismatchDetails {
+    pub benchset: String,
+    pub query: String,
+    #[source]
+    pub mismatch: ResultMismatch,
+}
+
+pub(crate) fn compare(
+    reference: &str,
+    expected: &ResultSummary,
+    target: &str,
+    actual: &ResultSummary,
+) -> Result<(), ResultMismatch> {
+    if expected.count != actual.count {
+        return Err(ResultMismatch::Count {
+            reference: reference.to_owned(),
+            target: target.to_owned(),
+            expected: expected.count,
+            actual: actual.count,
+        });
+    }
+
+    if let (Some(expected), Some(actual)) = (&expected.values, &actual.values) {
+        let first_difference = (0..expected.len().max(actual.len())).find(|&i| expected.get(i) != actual.get(i));
+
+        if let Some(index) = first_difference {
+            return Err(ResultMismatch::Values {
+                reference: reference.to_owned(),
+                target: target.to_owned(),
+                index,
+                expected: expected.get(index).cloned(),
+                actual: actual.get(index).cloned(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+struct DisplayValue<'a>(&'a Option<String>);
+
+impl Display for DisplayValue<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{value}"),
+            None => write!(f, "[none]"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn nested_arrays(depth: usize) -> Vec<u8> {
+        format!("{}{}", "[".repeat(depth), "]".repeat(depth)).into_bytes()
+    }
+
+    #[test]
+    fn matches_too_deep_to_parse_are_only_counted() {
+        let deep = nested_arrays(2048);
+        let summary = ResultSummary::from_json_bytes([b"1".as_slice(), &deep, b"2"]).unwrap();
+
+        assert_eq!(summary, ResultSummary::from_count(3));
+    }
+
+    #[test]
+    fn invalid_matches_are_errors() {
+        assert!(ResultSummary::from_json_bytes([nested_arrays(4).as_slice(), b"{"]).is_err());
+    }
+
+    #[test]
+    fn same_values_in_different_order_match() {
+        let expected = ResultSummary::from_values(&[json!(1), json!("a")]);
+        let actual = ResultSummary::from_values(&[json!("a"), json!(1)]);
+
+        assert!(compare("reference", &expected, "target", &actual).is_ok());
+    }
+
+    #[test]
+    fn count_mismatch() {
+        let expected = ResultSummary::from_count(2);
+        let actual = ResultSummary::from_values(&[json!(1)]);
+
+        let err = compare("reference", &expected, "target", &actual).unwrap_err();
+
+        assert!(matches!(
+            err,
+            ResultMismatch::Count {
+                expected: 2,
+                actual: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn value_mismatch_reports_first_difference() {
+        let expected = ResultSummary::from_values(&[json!(1), json!(2), json!(3)]);
+        let actual = ResultSummary::from_values(&[json!(1), json!(3), json!(4)]);
+
+        let err = compare("reference", &expected, "target", &actual).unwrap_err();
+
+        match err {
+            ResultMismatch::Values {
+                index,
+                expected,
+                actual,
+                ..
+            } => {
+                assert_eq!(index, 1);
+                assert_eq!(expected.as_deref(), Some("2"));
+                assert_eq!(actual.as_deref(), Some("3"));
+            }
+            ResultMismatch::Count { .. } => panic!("expected a value mismatch, got {err}"),
+        }
+    }
+
+    #[test]
+    fn count_only_summary_matches_values_with_the_same_count() {
+        let count = ResultSummary::from_count(2);
+        let values = ResultSummary::from_values(&[json!({"a": 1}), json!([])]);
+
+        assert!(compare("reference", &count, "target", &values).is_ok());
+        assert!(compare("reference", &values, "target", &count).is_ok());
+    }
+
+    #[test]
+    fn combined_summaries_keep_values_only_if_all_have_them() {
+        let with_values = ResultSummary::combine([
+            ResultSummary::from_values(&[json!(2)]),
+            ResultSummary::from_values(&[json!(1)]),
+        ]);
+        let mixed = ResultSummary::combine([ResultSummary::from_values(&[json!(1)]), ResultSummary::from_count(3)]);
+
+        assert_eq!(with_values.count(), 2);
+        assert_eq!(with_values.values(), Some(&["1".to_owned(), "2".to_owned()][..]));
+        assert_eq!(mixed.count(), 4);
+        assert_eq!(mixed.values(), None);
+    }
+}
@@ -1,4 +1,13 @@
+pub mod external;
+pub mod jaq;
+#[cfg(feature = "jsonpath_lib")]
+pub mod jsonpath_lib;
 pub mod jsonpath_rust;
+#[cfg(feature = "jsonski")]
+pub mod jsonski;
 pub mod rsonpath;
+#[cfg(feature = "jsurfer")]
 pub mod rust_jsurfer;
+pub mod serde_json_baseline;
 pub mod serde_json_path;
+mod simple_query;
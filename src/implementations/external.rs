@@ -0,0 +1,333 @@
+//! Engines running in a separate process, driven over a line protocol on the child's stdin and stdout.
+//!
+//! This allows benchmarking engines written in any language without linking them into the harness.
+//! The child is started once per target and kept alive across iterations, so process startup is not measured.
+//!
+//! Every command is a single line and is answered with a single line,
+//! either `ok` followed by the result or `err` followed by a message.
+//!
+//! | Command                 | Response               | Meaning |
+//! |-------------------------|------------------------|---|
+//! | `load <path>`           | `ok <handle>`          | Load the file at `<path>`, the rest of the line. |
+//! | `load_record <json>`    | `ok <handle>`          | Load a single JSON Lines record given inline. |
+//! | `compile <query>`       | `ok <handle>`          | Compile the JSONPath query, the rest of the line. |
+//! | `run <query> <file>`    | `ok <count> [<nanos>]` | Run a query on a file, returning the match count and optionally the run time. |
+//! | `free <handle>`         | `ok`                   | Release a loaded file or compiled query. |
+//! | `quit`                  | none                   | Exit. The engine should also exit when its stdin is closed. |
+//!
+//! Handles are arbitrary tokens without whitespace chosen by the engine. The run time is in nanoseconds
+//! and, if given, is measured instead of the round trip, see [`Implementation::reported_time`].
+//! Anything the engine writes to stderr is passed through.
+use crate::framework::{
+    implementation::{Implementation, LoadRecord},
+    verification::ResultSummary,
+    ResultType,
+};
+use serde::Deserialize;
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    rc::Rc,
+    thread,
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+
+/// How long an engine has to exit after being asked to quit before it is killed.
+const EXIT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Program and arguments used to start an external engine.
+///
+/// In suite files it is given as a list, e.g. `command = ["python3", "engines/python_json.py"]`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "Vec<String>")]
+pub struct ExternalCommand {
+    name: String,
+    program: PathBuf,
+    args: Vec<String>,
+}
+
+/// Engine running in a child process, see the [module documentation](self) for the protocol.
+pub struct ExternalEngine {
+    process: Rc<RefCell<Process>>,
+}
+
+/// Loaded file or compiled query living in the engine process. Released when dropped.
+pub struct ExternalHandle {
+    id: String,
+    process: Rc<RefCell<Process>>,
+}
+
+pub struct ExternalResult {
+    count: u64,
+    elapsed: Option<Duration>,
+}
+
+struct Process {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+    line: String,
+}
+
+impl ExternalCommand {
+    /// Command running `program` without arguments, named after the program's file name.
+    pub fn new<P: AsRef<Path>>(program: P) -> Self {
+        let program = program.as_ref().to_owned();
+        let name = program
+            .file_stem()
+            .map_or_else(|| "external".to_owned(), |stem| stem.to_string_lossy().into_owned());
+
+        Self {
+            name,
+            program,
+            args: vec![],
+        }
+    }
+
+    /// Use `name` as the default id of targets running this command.
+    pub fn named(self, name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..self
+        }
+    }
+
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.args.push(arg.to_owned());
+        self
+    }
+
+    pub fn args<I: IntoIterator<Item = S>, S: AsRef<str>>(self, args: I) -> Self {
+        args.into_iter().fold(self, |command, arg| command.arg(arg.as_ref()))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn program(&self) -> &Path {
+        &self.program
+    }
+}
+
+impl TryFrom<Vec<String>> for ExternalCommand {
+    type Error = &'static str;
+
+    fn try_from(value: Vec<String>) -> Result<Self, Self::Error> {
+        let mut parts = value.into_iter();
+        let program = parts
+            .next()
+            .ok_or("the command of an external engine cannot be empty")?;
+
+        Ok(Self::new(program).args(parts))
+    }
+}
+
+impl ExternalEngine {
+    pub fn spawn(command: &ExternalCommand) -> Result<Self, ExternalEngineError> {
+        let mut child = Command::new(&command.program)
+            .args(&command.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|err| ExternalEngineError::SpawnError(command.program.clone(), err))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+        Ok(Self {
+            process: Rc::new(RefCell::new(Process {
+                child,
+                stdin: Some(stdin),
+                stdout,
+                line: String::new(),
+            })),
+        })
+    }
+
+    fn request_handle(&self, command: &str, argument: &str) -> Result<ExternalHandle, ExternalEngineError> {
+        if argument.contains('\n') {
+            return Err(ExternalEngineError::MultilineArgument(command.to_owned()));
+        }
+        let id = self.process.borrow_mut().request(&format!("{command} {argument}"))?;
+
+        if id.is_empty() || id.contains(char::is_whitespace) {
+            return Err(ExternalEngineError::MalformedResponse(command.to_owned(), id));
+        }
+
+        Ok(ExternalHandle {
+            id,
+            process: self.process.clone(),
+        })
+    }
+}
+
+impl Implementation for ExternalEngine {
+    type Query = ExternalHandle;
+
+    type File = ExternalHandle;
+
+    type Error = ExternalEngineError;
+
+    type Result<'a> = ExternalResult;
+
+    fn id() -> &'static str {
+        "external"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Count
+    }
+
+    fn reports_time() -> bool {
+        true
+    }
+
+    fn reported_time(result: &Self::Result<'_>) -> Option<Duration> {
+        result.elapsed
+    }
+
+    /// External engines need a command to run, use [`ExternalEngine::spawn`] instead.
+    fn new() -> Result<Self, Self::Error> {
+        Err(ExternalEngineError::NoCommand)
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        self.request_handle("load", file_path)
+    }
+
+    fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+        self.request_handle("compile", query)
+    }
+
+    fn run<'a>(&self, query: &'a Self::Query, file: &'a Self::File) -> Result<Self::Result<'a>, Self::Error> {
+        let response = self
+            .process
+            .borrow_mut()
+            .request(&format!("run {} {}", query.id, file.id))?;
+        let malformed = || ExternalEngineError::MalformedResponse("run".to_owned(), response.clone());
+        let mut parts = response.split_whitespace();
+
+        let count = parts.next().and_then(|c| c.parse().ok()).ok_or_else(malformed)?;
+        let elapsed = match parts.next() {
+            Some(nanos) => Some(Duration::from_nanos(nanos.parse().map_err(|_| malformed())?)),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return Err(malformed());
+        }
+
+        Ok(ExternalResult { count, elapsed })
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        Ok(ResultSummary::from_count(self.run(query, file)?.count))
+    }
+}
+
+impl LoadRecord for ExternalEngine {
+    fn load_record(&self, record: &str) -> Result<Self::File, Self::Error> {
+        self.request_handle("load_record", record)
+    }
+}
+
+impl ExternalResult {
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Time the engine reported for the run, excluding communication with the harness.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+}
+
+impl Process {
+    /// Send a command and return the rest of the `ok` response.
+    fn request(&mut self, command: &str) -> Result<String, ExternalEngineError> {
+        let stdin = self.stdin.as_mut().ok_or(ExternalEngineError::EngineExited)?;
+        stdin.write_all(format!("{command}\n").as_bytes())?;
+        stdin.flush()?;
+
+        self.line.clear();
+        if self.stdout.read_line(&mut self.line)? == 0 {
+            return Err(ExternalEngineError::EngineExited);
+        }
+        let response = self.line.trim_end();
+        let command = command.split(' ').next().unwrap_or_default();
+
+        if let Some(message) = response.strip_prefix("err") {
+            Err(ExternalEngineError::EngineError(
+                command.to_owned(),
+                message.trim().to_owned(),
+            ))
+        } else if response == "ok" {
+            Ok(String::new())
+        } else if let Some(result) = response.strip_prefix("ok ") {
+            Ok(result.trim().to_owned())
+        } else {
+            Err(ExternalEngineError::MalformedResponse(
+                command.to_owned(),
+                response.to_owned(),
+            ))
+        }
+    }
+}
+
+impl Drop for ExternalHandle {
+    fn drop(&mut self) {
+        if let Ok(mut process) = self.process.try_borrow_mut() {
+            process.request(&format!("free {}", self.id)).ok();
+        }
+    }
+}
+
+impl Drop for Process {
+    /// Ask the engine to quit and close its stdin, killing it if it does not exit within [`EXIT_GRACE_PERIOD`].
+    fn drop(&mut self) {
+        if let Some(mut stdin) = self.stdin.take() {
+            stdin.write_all(b"quit\n").ok();
+        }
+        let deadline = Instant::now() + EXIT_GRACE_PERIOD;
+        while Instant::now() < deadline {
+            match self.child.try_wait() {
+                Ok(Some(_)) => return,
+                Ok(None) => thread::sleep(Duration::from_millis(10)),
+                Err(_) => break,
+            }
+        }
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+impl Display for ExternalResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.elapsed {
+            Some(elapsed) => write!(f, "{} (engine reported {elapsed:?})", self.count),
+            None => write!(f, "{}", self.count),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ExternalEngineError {
+    #[error("external engines cannot be created without a command to run")]
+    NoCommand,
+    #[error("error starting external engine {0}: {1}")]
+    SpawnError(PathBuf, #[source] io::Error),
+    #[error("error communicating with the external engine: {0}")]
+    IoError(#[from] io::Error),
+    #[error("the external engine exited unexpectedly")]
+    EngineExited,
+    #[error("the external engine failed to '{0}': {1}")]
+    EngineError(String, String),
+    #[error("the external engine responded to '{0}' with '{1}'")]
+    MalformedResponse(String, String),
+    #[error("argument of '{0}' spans multiple lines and cannot be sent to the external engine")]
+    MultilineArgument(String),
+}
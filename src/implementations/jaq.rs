@@ -0,0 +1,153 @@
+//! The jq clone [jaq](https://github.com/01mf02/jaq). It does not speak JSONPath,
+//! so [simple queries](SimpleQuery) are translated into equivalent jq filters.
+use super::simple_query::{Selector, SimpleQuery, Step};
+use crate::framework::{
+    implementation::{Implementation, LoadRecord},
+    verification::ResultSummary,
+    ResultType,
+};
+use jaq_core::{
+    load::{Arena, File, Loader},
+    Compiler, Ctx, Native, RcIter,
+};
+use jaq_json::Val;
+use serde_json::Value;
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, BufReader},
+};
+use thiserror::Error;
+
+pub struct Jaq {}
+
+pub type JaqFilter = jaq_core::Filter<Native<Val>>;
+
+pub struct JaqResult(Vec<Val>);
+
+impl Implementation for Jaq {
+    type Query = JaqFilter;
+
+    type File = Val;
+
+    type Error = JaqError;
+
+    type Result<'a> = JaqResult;
+
+    fn id() -> &'static str {
+        "jaq"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Full
+    }
+
+    fn new() -> Result<Self, Self::Error> {
+        Ok(Jaq {})
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        let file = fs::File::open(file_path)?;
+        let reader = BufReader::new(file);
+        let value: Value = serde_json::from_reader(reader)?;
+
+        Ok(Val::from(value))
+    }
+
+    fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+        let simple_query = SimpleQuery::parse(query).ok_or_else(|| JaqError::UnsupportedQuery(query.to_owned()))?;
+        let code = translate(&simple_query);
+
+        let arena = Arena::default();
+        let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+        let modules = loader
+            .load(&arena, File { code: &code, path: () })
+            .map_err(|errs| JaqError::CompileError(code.clone(), format!("{errs:?}")))?;
+
+        Compiler::default()
+            .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+            .compile(modules)
+            .map_err(|errs| JaqError::CompileError(code.clone(), format!("{errs:?}")))
+    }
+
+    fn run<'a>(&self, query: &'a Self::Query, file: &'a Self::File) -> Result<Self::Result<'a>, Self::Error> {
+        let inputs = RcIter::new(core::iter::empty());
+        let results = query
+            .run((Ctx::new([], &inputs), file.clone()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| JaqError::RuntimeError(err.to_string()))?;
+
+        Ok(JaqResult(results))
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        let JaqResult(results) = self.run(query, file)?;
+        let values: Vec<Value> = results.into_iter().map(Value::from).collect();
+
+        Ok(ResultSummary::from_values(&values))
+    }
+}
+
+impl LoadRecord for Jaq {
+    fn load_record(&self, record: &str) -> Result<Self::File, Self::Error> {
+        let value: Value = serde_json::from_str(record)?;
+
+        Ok(Val::from(value))
+    }
+}
+
+/// Translate the query into a jq filter selecting the same nodes in the same order.
+///
+/// Selectors must not produce anything for missing members or out of bounds indices,
+/// where jq would produce `null`, hence the explicit checks.
+fn translate(query: &SimpleQuery) -> String {
+    let filters: Vec<_> = query
+        .steps()
+        .iter()
+        .map(|step| match step {
+            Step::Child(selector) => translate_selector(selector),
+            Step::Descendant(selector) => format!(".. | {}", translate_selector(selector)),
+        })
+        .collect();
+
+    if filters.is_empty() {
+        ".".to_owned()
+    } else {
+        filters.join(" | ")
+    }
+}
+
+fn translate_selector(selector: &Selector) -> String {
+    match selector {
+        Selector::Name(name) => {
+            let key = serde_json::to_string(name).expect("strings always serialize");
+            format!("objects | select(has({key})) | .[{key}]")
+        }
+        Selector::Wildcard => ".[]?".to_owned(),
+        Selector::Index(index) => format!("arrays | select(length > {index}) | .[{index}]"),
+    }
+}
+
+impl Display for JaqResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for res in &self.0 {
+            writeln!(f, "{res}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum JaqError {
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+    #[error("error parsing JSON with serde: '{0}'")]
+    SerdeError(#[from] serde_json::Error),
+    #[error("query '{0}' cannot be translated into a jq filter")]
+    UnsupportedQuery(String),
+    #[error("error compiling jq filter '{0}': {1}")]
+    CompileError(String, String),
+    #[error("error running jq filter: {0}")]
+    RuntimeError(String),
+}
@@ -0,0 +1,89 @@
+use crate::framework::{
+    implementation::{Implementation, LoadRecord},
+    verification::ResultSummary,
+    ResultType,
+};
+use jsonpath_lib::{Compiled, JsonPathError};
+use serde_json::Value;
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, BufReader},
+};
+use thiserror::Error;
+
+pub struct JsonpathLib {}
+
+pub struct JsonpathLibResult<'a>(Vec<&'a Value>);
+
+impl Implementation for JsonpathLib {
+    type Query = Compiled;
+
+    type File = Value;
+
+    type Error = JsonpathLibError;
+
+    type Result<'a> = JsonpathLibResult<'a>;
+
+    fn id() -> &'static str {
+        "jsonpath_lib"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Full
+    }
+
+    fn new() -> Result<Self, Self::Error> {
+        Ok(JsonpathLib {})
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        let file = fs::File::open(file_path)?;
+        let reader = BufReader::new(file);
+        let value: Value = serde_json::from_reader(reader)?;
+
+        Ok(value)
+    }
+
+    fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+        Compiled::compile(query).map_err(JsonpathLibError::CompileError)
+    }
+
+    fn run<'a>(&self, query: &Self::Query, file: &'a Self::File) -> Result<Self::Result<'a>, Self::Error> {
+        Ok(JsonpathLibResult(query.select(file)?))
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        Ok(ResultSummary::from_values(query.select(file)?))
+    }
+}
+
+impl LoadRecord for JsonpathLib {
+    fn load_record(&self, record: &str) -> Result<Self::File, Self::Error> {
+        let value: Value = serde_json::from_str(record)?;
+
+        Ok(value)
+    }
+}
+
+impl<'a> Display for JsonpathLibResult<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for res in &self.0 {
+            writeln!(f, "{res}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum JsonpathLibError {
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+    #[error("error parsing JSON with serde: '{0}'")]
+    SerdeError(#[from] serde_json::Error),
+    #[error("error compiling the query with jsonpath_lib: '{0}'")]
+    CompileError(String),
+    #[error(transparent)]
+    SelectError(#[from] JsonPathError),
+}
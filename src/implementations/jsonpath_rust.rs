@@ -1,4 +1,8 @@
-use crate::framework::implementation::Implementation;
+use crate::framework::{
+    implementation::{Implementation, LoadRecord},
+    verification::ResultSummary,
+    ResultType,
+};
 use jsonpath_rust::{parser::JsonPath, JsonPathValue};
 use serde_json::Value;
 use std::{
@@ -26,6 +30,10 @@ impl Implementation for JsonpathRust {
         "jsonpath-rust"
     }
 
+    fn result_type() -> ResultType {
+        ResultType::Full
+    }
+
     fn new() -> Result<Self, Self::Error> {
         Ok(JsonpathRust {})
     }
@@ -47,6 +55,25 @@ impl Implementation for JsonpathRust {
 
         Ok(JsonpathRustResult(results))
     }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        let results = query.find_slice(file);
+        let values = results.iter().filter_map(|res| match res {
+            JsonPathValue::Slice(value, _) => Some(*value),
+            JsonPathValue::NewValue(value) => Some(value),
+            JsonPathValue::NoValue => None,
+        });
+
+        Ok(ResultSummary::from_values(values))
+    }
+}
+
+impl LoadRecord for JsonpathRust {
+    fn load_record(&self, record: &str) -> Result<Self::File, Self::Error> {
+        let value: Value = serde_json::from_str(record)?;
+
+        Ok(value)
+    }
 }
 
 impl<'a> Display for JsonpathRustResult<'a> {
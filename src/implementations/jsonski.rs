@@ -0,0 +1,119 @@
+//! [JSONSki](https://github.com/AutomataLab/JSONSki), built from the vendored sources in
+//! `src/implementations/jsonski` and called through the shim in `src/implementations/jsonskiShim`.
+use crate::framework::{implementation::Implementation, verification::ResultSummary, ResultType};
+use std::{
+    ffi::{c_char, c_long, CString, NulError},
+    ptr::NonNull,
+};
+use thiserror::Error;
+
+#[repr(C)]
+struct Record {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct QueryProcessor {
+    _private: [u8; 0],
+}
+
+extern "C" {
+    fn jsonski_load_file(file_path: *const c_char) -> *mut Record;
+    fn jsonski_free_record(record: *mut Record);
+    fn jsonski_compile(query: *const c_char) -> *mut QueryProcessor;
+    fn jsonski_free_query(processor: *mut QueryProcessor);
+    fn jsonski_run(processor: *mut QueryProcessor, record: *mut Record) -> c_long;
+}
+
+pub struct JsonSki {}
+
+/// The whole file loaded into memory by JSONSki.
+pub struct JsonSkiRecord(NonNull<Record>);
+
+/// Query automaton built by JSONSki, reused for every run.
+pub struct JsonSkiQuery {
+    processor: NonNull<QueryProcessor>,
+    query: String,
+}
+
+impl Implementation for JsonSki {
+    type Query = JsonSkiQuery;
+
+    type File = JsonSkiRecord;
+
+    type Error = JsonSkiError;
+
+    type Result<'a> = u64;
+
+    fn id() -> &'static str {
+        "jsonski"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Count
+    }
+
+    fn new() -> Result<Self, Self::Error> {
+        Ok(JsonSki {})
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        let c_path = CString::new(file_path)?;
+        // SAFETY: the path is a valid NUL-terminated string that the shim does not keep.
+        let record = unsafe { jsonski_load_file(c_path.as_ptr()) };
+
+        NonNull::new(record)
+            .map(JsonSkiRecord)
+            .ok_or_else(|| JsonSkiError::LoadError(file_path.to_owned()))
+    }
+
+    fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+        let c_query = CString::new(query)?;
+        // SAFETY: the query is a valid NUL-terminated string that the shim does not keep.
+        let processor = unsafe { jsonski_compile(c_query.as_ptr()) };
+
+        NonNull::new(processor)
+            .map(|processor| JsonSkiQuery {
+                processor,
+                query: query.to_owned(),
+            })
+            .ok_or_else(|| JsonSkiError::CompileError(query.to_owned()))
+    }
+
+    fn run<'a>(&self, query: &'a Self::Query, file: &'a Self::File) -> Result<Self::Result<'a>, Self::Error> {
+        // SAFETY: the processor and the record are alive until `query` and `file` are dropped.
+        let count = unsafe { jsonski_run(query.processor.as_ptr(), file.0.as_ptr()) };
+
+        u64::try_from(count).map_err(|_| JsonSkiError::QueryError(query.query.clone()))
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        Ok(ResultSummary::from_count(self.run(query, file)?))
+    }
+}
+
+impl Drop for JsonSkiRecord {
+    fn drop(&mut self) {
+        // SAFETY: the record was returned by `jsonski_load_file` and is freed only here.
+        unsafe { jsonski_free_record(self.0.as_ptr()) }
+    }
+}
+
+impl Drop for JsonSkiQuery {
+    fn drop(&mut self) {
+        // SAFETY: the processor was returned by `jsonski_compile` and is freed only here.
+        unsafe { jsonski_free_query(self.processor.as_ptr()) }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum JsonSkiError {
+    #[error("strings passed to JSONSki cannot contain NUL bytes: {0}")]
+    NulError(#[from] NulError),
+    #[error("JSONSki failed to load file '{0}'")]
+    LoadError(String),
+    #[error("JSONSki failed to compile query '{0}'")]
+    CompileError(String),
+    #[error("JSONSki failed to run query '{0}'")]
+    QueryError(String),
+}
@@ -0,0 +1,59 @@
+// C interface to the vendored JSONSki sources in src/implementations/jsonski, used by jsonski.rs.
+#include <string>
+
+#include "../jsonski/src/QueryProcessor.h"
+#include "../jsonski/src/RecordLoader.h"
+
+extern "C" {
+
+Record* jsonski_load_file(const char* file_path) {
+    try {
+        return RecordLoader::loadSingleRecord(const_cast<char*>(file_path));
+    } catch (...) {
+        return nullptr;
+    }
+}
+
+void jsonski_free_record(Record* record) {
+    delete record;
+}
+
+// Build the query automaton, returning nullptr on failure. It is reused for every run of the query.
+QueryProcessor* jsonski_compile(const char* query) {
+    try {
+        return new QueryProcessor(query);
+    } catch (...) {
+        return nullptr;
+    }
+}
+
+void jsonski_free_query(QueryProcessor* processor) {
+    delete processor;
+}
+
+// Run a compiled query over the record, returning the number of matches or -1 on failure.
+// JSONSki returns the matches as text, one per line.
+long jsonski_run(QueryProcessor* processor, Record* record) {
+    try {
+        std::string output = processor->runQuery(record);
+
+        long count = 0;
+        size_t line_start = 0;
+        while (line_start < output.size()) {
+            size_t line_end = output.find('\n', line_start);
+            if (line_end == std::string::npos) {
+                line_end = output.size();
+            }
+            if (line_end > line_start) {
+                count += 1;
+            }
+            line_start = line_end + 1;
+        }
+
+        return count;
+    } catch (...) {
+        return -1;
+    }
+}
+
+}
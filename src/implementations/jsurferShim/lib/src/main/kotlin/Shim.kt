@@ -23,6 +23,11 @@ object Shim {
         return JsonFile(json)
     }
 
+    @JvmStatic
+    fun loadRecord(record: String): JsonFile {
+        return JsonFile(record)
+    }
+
     @JvmStatic
     fun compileQuery(query: String): CompiledQuery {
         var result = 0L
@@ -33,6 +38,7 @@ object Shim {
                         .bind(compiledPath, JsonPathListener { _, _ -> result += 1L })
                         .build()
         return CompiledQuery { file ->
+            result = 0L
             surfer.surf(file.contents, config)
             result
         }
@@ -1,22 +1,153 @@
-use crate::framework::implementation::Implementation;
+use crate::{
+    dataset::Compression,
+    framework::{
+        implementation::{Implementation, LoadRecord},
+        verification::ResultSummary,
+        ResultType,
+    },
+};
 use ouroboros::self_referencing;
 use rsonpath::{
     engine::main::MainEngine,
-    input::OwnedBytes,
-    result::{Match, Sink},
+    input::{Input, OwnedBytes},
+    result::{Match, MatchCount, MatchWriter, Sink},
 };
 use rsonpath::{
     engine::{Compiler, Engine},
-    input::MmapInput,
+    input::{BufferedInput, MmapInput},
 };
 use rsonpath_syntax::JsonPathQuery;
-use std::{convert::Infallible, fmt::Display, fs, io};
+use serde::Deserialize;
+use std::{
+    cell::RefCell,
+    convert::Infallible,
+    fmt::Display,
+    fs,
+    io::{self, Read, Seek, SeekFrom},
+};
 use thiserror::Error;
 
-pub struct Rsonpath {}
+pub struct Rsonpath {
+    collection: MatchCollection,
+    stored: RefCell<Vec<Match>>,
+}
 pub struct RsonpathCount {}
-pub struct RsonpathMmap {}
+pub struct RsonpathMmap {
+    collection: MatchCollection,
+    stored: RefCell<Vec<Match>>,
+}
 pub struct RsonpathMmapCount {}
+pub struct RsonpathBuffered {
+    collection: MatchCollection,
+    stored: RefCell<Vec<Match>>,
+}
+pub struct RsonpathBufferedCount {}
+/// Reads a compressed copy of the dataset through a streaming decoder.
+pub struct RsonpathCompressed {
+    compression: Compression,
+    collection: MatchCollection,
+    stored: RefCell<Vec<Match>>,
+}
+pub struct RsonpathCompressedCount {
+    compression: Compression,
+}
+pub struct RsonpathIndices {}
+pub struct RsonpathApproxSpans {}
+pub struct RsonpathMmapIndices {}
+pub struct RsonpathMmapApproxSpans {}
+pub struct RsonpathBufferedIndices {}
+pub struct RsonpathBufferedApproxSpans {}
+
+/// What a full rsonpath target does with the matches it finds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchCollection {
+    /// Drop every match immediately.
+    Discard,
+    /// Collect matches into a fresh `Vec` and return them as the result.
+    Collect,
+    /// Store matches into a `Vec` reused between runs, so that its capacity is already reserved.
+    /// Room for all matches is reserved by an untimed run when the target is prepared.
+    Store,
+    /// Write every match to an [`io::Sink`].
+    Write,
+}
+
+impl MatchCollection {
+    /// Suffix distinguishing the target id from the default, discarding one.
+    pub fn id_suffix(&self) -> Option<&'static str> {
+        match self {
+            Self::Discard => None,
+            Self::Collect => Some("collect"),
+            Self::Store => Some("store"),
+            Self::Write => Some("write"),
+        }
+    }
+}
+
+pub enum RsonpathResult {
+    NotCollected,
+    Matches(MatchDisplay),
+}
+
+impl Rsonpath {
+    pub fn collecting() -> Self {
+        Self::with_collection(MatchCollection::Collect)
+    }
+
+    pub fn with_collection(collection: MatchCollection) -> Self {
+        Rsonpath {
+            collection,
+            stored: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl RsonpathMmap {
+    pub fn collecting() -> Self {
+        Self::with_collection(MatchCollection::Collect)
+    }
+
+    pub fn with_collection(collection: MatchCollection) -> Self {
+        RsonpathMmap {
+            collection,
+            stored: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl RsonpathBuffered {
+    pub fn collecting() -> Self {
+        Self::with_collection(MatchCollection::Collect)
+    }
+
+    pub fn with_collection(collection: MatchCollection) -> Self {
+        RsonpathBuffered {
+            collection,
+            stored: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl RsonpathCompressed {
+    pub fn with_compression(compression: Compression) -> Self {
+        Self::with_compression_and_collection(compression, MatchCollection::Discard)
+    }
+
+    pub fn with_compression_and_collection(compression: Compression, collection: MatchCollection) -> Self {
+        RsonpathCompressed {
+            compression,
+            collection,
+            stored: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl RsonpathCompressedCount {
+    pub fn with_compression(compression: Compression) -> Self {
+        RsonpathCompressedCount { compression }
+    }
+}
 
 #[self_referencing()]
 pub struct RsonpathQuery {
@@ -33,39 +164,42 @@ impl Implementation for Rsonpath {
 
     type Error = RsonpathError;
 
-    type Result<'a> = &'static str;
+    type Result<'a> = RsonpathResult;
 
     fn id() -> &'static str {
         "rsonpath"
     }
 
+    fn result_type() -> ResultType {
+        ResultType::Full
+    }
+
+    fn uses_simd() -> bool {
+        true
+    }
+
     fn new() -> Result<Self, Self::Error> {
-        Ok(Rsonpath {})
+        Ok(Self::with_collection(MatchCollection::Discard))
     }
 
     fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
-        let file = fs::read_to_string(file_path)?;
-        let input = OwnedBytes::new(file.into_bytes());
-
-        Ok(input)
+        load_owned(file_path)
     }
 
     fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
-        let query = rsonpath_syntax::parse(query).unwrap();
-
-        let rsonpath = RsonpathQuery::try_new(query, |query| {
-            MainEngine::compile_query(query).map_err(RsonpathError::CompilerError)
-        })?;
+        compile_query(query)
+    }
 
-        Ok(rsonpath)
+    fn warm_up(&self, file_path: &str, query: &str) -> Result<(), Self::Error> {
+        store_matches_once(self, self.collection, file_path, query)
     }
 
     fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
-        query
-            .with_engine(|engine| engine.matches(file, &mut VoidSink))
-            .map_err(RsonpathError::EngineError)?;
+        run_matches(query, file, self.collection, &self.stored)
+    }
 
-        Ok("[not collected]")
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        summarize_matches(query, file)
     }
 }
 
@@ -76,39 +210,40 @@ impl Implementation for RsonpathCount {
 
     type Error = RsonpathError;
 
-    type Result<'a> = &'static str;
+    type Result<'a> = MatchCount;
 
     fn id() -> &'static str {
         "rsonpath_count"
     }
 
+    fn result_type() -> ResultType {
+        ResultType::Count
+    }
+
+    fn uses_simd() -> bool {
+        true
+    }
+
     fn new() -> Result<Self, Self::Error> {
         Ok(RsonpathCount {})
     }
 
     fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
-        let file = fs::read_to_string(file_path)?;
-        let input = OwnedBytes::new(file.into_bytes());
-
-        Ok(input)
+        load_owned(file_path)
     }
 
     fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
-        let query = rsonpath_syntax::parse(query).unwrap();
-
-        let rsonpath = RsonpathQuery::try_new(query, |query| {
-            MainEngine::compile_query(query).map_err(RsonpathError::CompilerError)
-        })?;
-
-        Ok(rsonpath)
+        compile_query(query)
     }
 
     fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
         query
             .with_engine(|engine| engine.count(file))
-            .map_err(RsonpathError::EngineError)?;
+            .map_err(RsonpathError::EngineError)
+    }
 
-        Ok("[not collected]")
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        summarize_count(query, file)
     }
 }
 
@@ -119,39 +254,42 @@ impl Implementation for RsonpathMmap {
 
     type Error = RsonpathError;
 
-    type Result<'a> = &'static str;
+    type Result<'a> = RsonpathResult;
 
     fn id() -> &'static str {
         "rsonpath_mmap"
     }
 
+    fn result_type() -> ResultType {
+        ResultType::Full
+    }
+
+    fn uses_simd() -> bool {
+        true
+    }
+
     fn new() -> Result<Self, Self::Error> {
-        Ok(RsonpathMmap {})
+        Ok(Self::with_collection(MatchCollection::Discard))
     }
 
     fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
-        let file = fs::File::open(file_path)?;
-        let input = unsafe { MmapInput::map_file(&file)? };
-
-        Ok(input)
+        load_mmap(file_path)
     }
 
     fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
-        let query = rsonpath_syntax::parse(query).unwrap();
-
-        let rsonpath = RsonpathQuery::try_new(query, |query| {
-            MainEngine::compile_query(query).map_err(RsonpathError::CompilerError)
-        })?;
+        compile_query(query)
+    }
 
-        Ok(rsonpath)
+    fn warm_up(&self, file_path: &str, query: &str) -> Result<(), Self::Error> {
+        store_matches_once(self, self.collection, file_path, query)
     }
 
     fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
-        query
-            .with_engine(|engine| engine.matches(file, &mut VoidSink))
-            .map_err(RsonpathError::EngineError)?;
+        run_matches(query, file, self.collection, &self.stored)
+    }
 
-        Ok("[not collected]")
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        summarize_matches(query, file)
     }
 }
 
@@ -162,44 +300,497 @@ impl Implementation for RsonpathMmapCount {
 
     type Error = RsonpathError;
 
-    type Result<'a> = &'static str;
+    type Result<'a> = MatchCount;
 
     fn id() -> &'static str {
         "rsonpath_mmap_count"
     }
 
+    fn result_type() -> ResultType {
+        ResultType::Count
+    }
+
+    fn uses_simd() -> bool {
+        true
+    }
+
     fn new() -> Result<Self, Self::Error> {
         Ok(RsonpathMmapCount {})
     }
 
     fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
-        let file = fs::File::open(file_path)?;
-        let input = unsafe { MmapInput::map_file(&file)? };
+        load_mmap(file_path)
+    }
 
-        Ok(input)
+    fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+        compile_query(query)
+    }
+
+    fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
+        query
+            .with_engine(|engine| engine.count(file))
+            .map_err(RsonpathError::EngineError)
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        summarize_count(query, file)
+    }
+}
+
+impl Implementation for RsonpathBuffered {
+    type Query = RsonpathQuery;
+
+    type File = fs::File;
+
+    type Error = RsonpathError;
+
+    type Result<'a> = RsonpathResult;
+
+    fn id() -> &'static str {
+        "rsonpath_buffered"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Full
+    }
+
+    fn uses_simd() -> bool {
+        true
+    }
+
+    fn new() -> Result<Self, Self::Error> {
+        Ok(Self::with_collection(MatchCollection::Discard))
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        load_buffered(file_path)
     }
 
     fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
-        let query = rsonpath_syntax::parse(query).unwrap();
+        compile_query(query)
+    }
+
+    fn warm_up(&self, file_path: &str, query: &str) -> Result<(), Self::Error> {
+        store_matches_once(self, self.collection, file_path, query)
+    }
+
+    fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
+        run_matches(query, &buffered_input(file)?, self.collection, &self.stored)
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        summarize_matches(query, &buffered_input(file)?)
+    }
+}
+
+impl Implementation for RsonpathBufferedCount {
+    type Query = RsonpathQuery;
+
+    type File = fs::File;
+
+    type Error = RsonpathError;
+
+    type Result<'a> = MatchCount;
 
-        let rsonpath = RsonpathQuery::try_new(query, |query| {
-            MainEngine::compile_query(query).map_err(RsonpathError::CompilerError)
-        })?;
+    fn id() -> &'static str {
+        "rsonpath_buffered_count"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Count
+    }
+
+    fn uses_simd() -> bool {
+        true
+    }
+
+    fn new() -> Result<Self, Self::Error> {
+        Ok(RsonpathBufferedCount {})
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        load_buffered(file_path)
+    }
 
-        Ok(rsonpath)
+    fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+        compile_query(query)
     }
 
     fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
+        let input = buffered_input(file)?;
         query
-            .with_engine(|engine| engine.count(file))
-            .map_err(RsonpathError::EngineError)?;
+            .with_engine(|engine| engine.count(&input))
+            .map_err(RsonpathError::EngineError)
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        summarize_count(query, &buffered_input(file)?)
+    }
+}
+
+impl Implementation for RsonpathCompressed {
+    type Query = RsonpathQuery;
+
+    type File = fs::File;
+
+    type Error = RsonpathError;
+
+    type Result<'a> = RsonpathResult;
+
+    fn id() -> &'static str {
+        "rsonpath_compressed"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Full
+    }
+
+    fn uses_simd() -> bool {
+        true
+    }
+
+    fn new() -> Result<Self, Self::Error> {
+        Ok(Self::with_compression(Compression::Gzip))
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        load_compressed(file_path, self.compression)
+    }
+
+    fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+        compile_query(query)
+    }
+
+    fn warm_up(&self, file_path: &str, query: &str) -> Result<(), Self::Error> {
+        store_matches_once(self, self.collection, file_path, query)
+    }
+
+    fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
+        run_matches(
+            query,
+            &decompressed_input(file, self.compression)?,
+            self.collection,
+            &self.stored,
+        )
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        summarize_matches(query, &decompressed_input(file, self.compression)?)
+    }
+}
+
+impl Implementation for RsonpathCompressedCount {
+    type Query = RsonpathQuery;
+
+    type File = fs::File;
+
+    type Error = RsonpathError;
+
+    type Result<'a> = MatchCount;
 
-        Ok("[not collected]")
+    fn id() -> &'static str {
+        "rsonpath_compressed_count"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Count
+    }
+
+    fn uses_simd() -> bool {
+        true
+    }
+
+    fn new() -> Result<Self, Self::Error> {
+        Ok(Self::with_compression(Compression::Gzip))
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        load_compressed(file_path, self.compression)
+    }
+
+    fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+        compile_query(query)
+    }
+
+    fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
+        let input = decompressed_input(file, self.compression)?;
+        query
+            .with_engine(|engine| engine.count(&input))
+            .map_err(RsonpathError::EngineError)
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        summarize_count(query, &decompressed_input(file, self.compression)?)
     }
 }
 
+macro_rules! rsonpath_input {
+    (direct, $file:expr) => {
+        $file
+    };
+    (buffered, $file:expr) => {
+        &buffered_input($file)?
+    };
+}
+
+/// Adapters for the result modes that only report positions of matches:
+/// [`Engine::indices`] and [`Engine::approximate_spans`]. Matches are discarded while benchmarking.
+macro_rules! rsonpath_position_adapter {
+    ($name:ident, $id:literal, $file:ty, $load:ident, $input:ident, $result_type:expr, $method:ident) => {
+        impl Implementation for $name {
+            type Query = RsonpathQuery;
+
+            type File = $file;
+
+            type Error = RsonpathError;
+
+            type Result<'a> = RsonpathResult;
+
+            fn id() -> &'static str {
+                $id
+            }
+
+            fn result_type() -> ResultType {
+                $result_type
+            }
+
+            fn uses_simd() -> bool {
+                true
+            }
+
+            fn new() -> Result<Self, Self::Error> {
+                Ok($name {})
+            }
+
+            fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+                $load(file_path)
+            }
+
+            fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+                compile_query(query)
+            }
+
+            fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
+                let input = rsonpath_input!($input, file);
+                query
+                    .with_engine(|engine| engine.$method(input, &mut VoidSink))
+                    .map_err(RsonpathError::EngineError)?;
+
+                Ok(RsonpathResult::NotCollected)
+            }
+
+            fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+                let input = rsonpath_input!($input, file);
+                let mut sink = CountSink(0);
+                query
+                    .with_engine(|engine| engine.$method(input, &mut sink))
+                    .map_err(RsonpathError::EngineError)?;
+
+                Ok(ResultSummary::from_count(sink.0))
+            }
+        }
+    };
+}
+
+rsonpath_position_adapter!(
+    RsonpathIndices,
+    "rsonpath_indices",
+    OwnedBytes<Vec<u8>>,
+    load_owned,
+    direct,
+    ResultType::Indices,
+    indices
+);
+rsonpath_position_adapter!(
+    RsonpathApproxSpans,
+    "rsonpath_approx_spans",
+    OwnedBytes<Vec<u8>>,
+    load_owned,
+    direct,
+    ResultType::ApproxSpans,
+    approximate_spans
+);
+rsonpath_position_adapter!(
+    RsonpathMmapIndices,
+    "rsonpath_mmap_indices",
+    MmapInput,
+    load_mmap,
+    direct,
+    ResultType::Indices,
+    indices
+);
+rsonpath_position_adapter!(
+    RsonpathMmapApproxSpans,
+    "rsonpath_mmap_approx_spans",
+    MmapInput,
+    load_mmap,
+    direct,
+    ResultType::ApproxSpans,
+    approximate_spans
+);
+rsonpath_position_adapter!(
+    RsonpathBufferedIndices,
+    "rsonpath_buffered_indices",
+    fs::File,
+    load_buffered,
+    buffered,
+    ResultType::Indices,
+    indices
+);
+rsonpath_position_adapter!(
+    RsonpathBufferedApproxSpans,
+    "rsonpath_buffered_approx_spans",
+    fs::File,
+    load_buffered,
+    buffered,
+    ResultType::ApproxSpans,
+    approximate_spans
+);
+
+/// Records of a JSON Lines file are copied into owned bytes, same as whole files for the default input.
+/// There is no mmap or buffered equivalent for data that is already in memory.
+macro_rules! rsonpath_load_record {
+    ($($name:ident),*) => {
+        $(
+            impl LoadRecord for $name {
+                fn load_record(&self, record: &str) -> Result<Self::File, Self::Error> {
+                    Ok(OwnedBytes::new(record.as_bytes().to_vec()))
+                }
+            }
+        )*
+    };
+}
+
+rsonpath_load_record!(Rsonpath, RsonpathCount, RsonpathIndices, RsonpathApproxSpans);
+
+fn compile_query(query: &str) -> Result<RsonpathQuery, RsonpathError> {
+    let query = rsonpath_syntax::parse(query)?;
+
+    RsonpathQuery::try_new(query, |query| {
+        MainEngine::compile_query(query).map_err(RsonpathError::CompilerError)
+    })
+}
+
+fn load_owned(file_path: &str) -> Result<OwnedBytes<Vec<u8>>, RsonpathError> {
+    let file = fs::read_to_string(file_path)?;
+
+    Ok(OwnedBytes::new(file.into_bytes()))
+}
+
+fn load_mmap(file_path: &str) -> Result<MmapInput, RsonpathError> {
+    let file = fs::File::open(file_path)?;
+
+    Ok(unsafe { MmapInput::map_file(&file)? })
+}
+
+fn load_buffered(file_path: &str) -> Result<fs::File, RsonpathError> {
+    Ok(fs::File::open(file_path)?)
+}
+
+/// A buffered input reads its source only once, so every run gets a fresh one reading from the start of the file.
+fn buffered_input(file: &fs::File) -> Result<BufferedInput<&fs::File>, RsonpathError> {
+    let mut reader = file;
+    reader.seek(SeekFrom::Start(0))?;
+
+    Ok(BufferedInput::new(reader))
+}
+
+/// Open the compressed copy of the JSON file at `file_path`.
+fn load_compressed(file_path: &str, compression: Compression) -> Result<fs::File, RsonpathError> {
+    Ok(fs::File::open(compression.compressed_path(file_path))?)
+}
+
+/// Like [`buffered_input`], but decompressing the file on the fly, so decompression is part of every run.
+fn decompressed_input(
+    file: &fs::File,
+    compression: Compression,
+) -> Result<BufferedInput<Box<dyn Read + '_>>, RsonpathError> {
+    let mut reader = file;
+    reader.seek(SeekFrom::Start(0))?;
+
+    Ok(BufferedInput::new(compression.decoder(reader)?))
+}
+
+fn run_matches<I: Input>(
+    query: &RsonpathQuery,
+    file: &I,
+    collection: MatchCollection,
+    stored: &RefCell<Vec<Match>>,
+) -> Result<RsonpathResult, RsonpathError> {
+    match collection {
+        MatchCollection::Discard => {
+            query
+                .with_engine(|engine| engine.matches(file, &mut VoidSink))
+                .map_err(RsonpathError::EngineError)?;
+
+            Ok(RsonpathResult::NotCollected)
+        }
+        MatchCollection::Collect => {
+            let mut matches: Vec<Match> = vec![];
+            query
+                .with_engine(|engine| engine.matches(file, &mut matches))
+                .map_err(RsonpathError::EngineError)?;
+
+            Ok(RsonpathResult::Matches(MatchDisplay(matches)))
+        }
+        MatchCollection::Store => {
+            let mut matches = stored.borrow_mut();
+            matches.clear();
+            query
+                .with_engine(|engine| engine.matches(file, &mut *matches))
+                .map_err(RsonpathError::EngineError)?;
+
+            Ok(RsonpathResult::NotCollected)
+        }
+        MatchCollection::Write => {
+            let mut writer = MatchWriter::from(io::sink());
+            query
+                .with_engine(|engine| engine.matches(file, &mut writer))
+                .map_err(RsonpathError::EngineError)?;
+
+            Ok(RsonpathResult::NotCollected)
+        }
+    }
+}
+
+/// With [`MatchCollection::Store`], run the query once so that the stored `Vec` already has room
+/// for all matches when the timed runs start.
+fn store_matches_once<I: Implementation>(
+    implementation: &I,
+    collection: MatchCollection,
+    file_path: &str,
+    query: &str,
+) -> Result<(), I::Error> {
+    if collection == MatchCollection::Store {
+        let file = implementation.load_file(file_path)?;
+        let query = implementation.compile_query(query)?;
+        implementation.run(&query, &file)?;
+    }
+
+    Ok(())
+}
+
+fn summarize_matches<I: Input>(query: &RsonpathQuery, file: &I) -> Result<ResultSummary, RsonpathError> {
+    let mut matches: Vec<Match> = vec![];
+    query
+        .with_engine(|engine| engine.matches(file, &mut matches))
+        .map_err(RsonpathError::EngineError)?;
+
+    ResultSummary::from_json_bytes(matches.iter().map(Match::bytes)).map_err(RsonpathError::MatchParseError)
+}
+
+fn summarize_count<I: Input>(query: &RsonpathQuery, file: &I) -> Result<ResultSummary, RsonpathError> {
+    let count = query
+        .with_engine(|engine| engine.count(file))
+        .map_err(RsonpathError::EngineError)?;
+
+    Ok(ResultSummary::from_count(count))
+}
+
 #[derive(Error, Debug)]
 pub enum RsonpathError {
+    #[error(transparent)]
+    ParseError(#[from] rsonpath_syntax::error::ParseError),
     #[error(transparent)]
     CompilerError(#[from] rsonpath::automaton::error::CompilerError),
     #[error(transparent)]
@@ -208,6 +799,8 @@ pub enum RsonpathError {
     InputError(#[from] rsonpath::input::error::InputError),
     #[error(transparent)]
     IoError(#[from] io::Error),
+    #[error("match returned by rsonpath is not valid JSON: {0}")]
+    MatchParseError(#[source] serde_json::Error),
     #[error("something happened")]
     Unknown(),
 }
@@ -217,15 +810,35 @@ pub struct MatchDisplay(Vec<Match>);
 impl Display for MatchDisplay {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for m in &self.0 {
-            writeln!(f, "{m}")?
+            writeln!(f, "{}", String::from_utf8_lossy(m.bytes()))?
         }
 
         Ok(())
     }
 }
 
+impl Display for RsonpathResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotCollected => write!(f, "[not collected]"),
+            Self::Matches(matches) => matches.fmt(f),
+        }
+    }
+}
+
 struct VoidSink;
 
+struct CountSink(u64);
+
+impl<D> Sink<D> for CountSink {
+    type Error = Infallible;
+
+    fn add_match(&mut self, _data: D) -> Result<(), Self::Error> {
+        self.0 += 1;
+        Ok(())
+    }
+}
+
 impl<D> Sink<D> for VoidSink {
     type Error = Infallible;
 
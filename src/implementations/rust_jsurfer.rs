@@ -1,4 +1,8 @@
-use crate::framework::implementation::Implementation;
+use crate::framework::{
+    implementation::{Implementation, LoadRecord},
+    verification::ResultSummary,
+    ResultType,
+};
 use jni::objects::{JClass, JObject};
 use jni::signature::{JavaType, Primitive, ReturnType, TypeSignature};
 use jni::{AttachGuard, InitArgsBuilder, JNIEnv, JNIVersion, JavaVM};
@@ -19,6 +23,7 @@ const QUERY_CLASS: &str = concat!(package!(), "/CompiledQuery");
 const FILE_CLASS: &str = concat!(package!(), "/JsonFile");
 const COMPILE_METHOD: &str = "compileQuery";
 const LOAD_METHOD: &str = "loadFile";
+const LOAD_RECORD_METHOD: &str = "loadRecord";
 const RUN_METHOD: &str = "run";
 const OVERHEAD_METHOD: &str = "overheadShim";
 
@@ -36,6 +41,10 @@ fn load_file_sig() -> String {
     format!("({}){}", string_type(), json_file_type())
 }
 
+fn load_record_sig() -> String {
+    format!("({}){}", string_type(), json_file_type())
+}
+
 fn compile_query_sig() -> String {
     format!("({}){}", string_type(), compiled_query_type())
 }
@@ -178,6 +187,10 @@ impl Implementation for JSurfer {
         "jsurfer"
     }
 
+    fn result_type() -> ResultType {
+        ResultType::Count
+    }
+
     fn new() -> Result<Self, Self::Error> {
         Ok(JSurfer {
             context: Jvm::attach()?,
@@ -234,6 +247,29 @@ impl Implementation for JSurfer {
                     .map_err(|err| JSurferError::ResultOutOfRange { value: l, source: err })
             })
     }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        self.run(query, file).map(ResultSummary::from_count)
+    }
+}
+
+impl LoadRecord for JSurfer {
+    fn load_record(&self, record: &str) -> Result<Self::File, Self::Error> {
+        let record_string = self.env().new_string(record)?;
+
+        let loaded_file = self.env().call_static_method(
+            self.shim(),
+            LOAD_RECORD_METHOD,
+            load_record_sig(),
+            &[(&record_string).into()],
+        )?;
+
+        let actual_type = loaded_file.type_name();
+        loaded_file
+            .l()
+            .map_err(|e| type_error(e, LOAD_RECORD_METHOD, "Object", actual_type))
+            .map(|f| LoadedFile { file_object: f })
+    }
 }
 
 #[derive(Error, Debug)]
@@ -252,7 +288,7 @@ pub enum JSurferError {
         expected: String,
         actual: String,
         #[source]
-        source: jni::errors::Error,
+        source: Box<jni::errors::Error>,
     },
     #[error("received result outside of u64 range: {value}")]
     ResultOutOfRange {
@@ -267,6 +303,6 @@ fn type_error(source: jni::errors::Error, method: &str, expected: &str, actual:
         method: method.to_owned(),
         expected: expected.to_owned(),
         actual: actual.to_owned(),
-        source,
+        source: Box::new(source),
     }
 }
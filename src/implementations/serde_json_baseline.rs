@@ -0,0 +1,180 @@
+//! Baselines parsing the document with plain `serde_json`, as a reference point for how fast the input
+//! could possibly be read.
+//!
+//! The whole document is parsed as part of the run, not when loading the file, so that the baselines measure
+//! parsing even if file load time is excluded from the measurement.
+use super::simple_query::{Selector, SimpleQuery, Step};
+use crate::framework::{
+    implementation::{Implementation, LoadRecord},
+    verification::ResultSummary,
+    ResultType,
+};
+use serde::de::IgnoredAny;
+use serde_json::Value;
+use std::{fs, io};
+use thiserror::Error;
+
+/// Parses the document into a [`Value`] and evaluates the query by traversing it.
+///
+/// Only [simple queries](SimpleQuery) are supported.
+pub struct SerdeJsonValue {}
+
+/// Parses the document into [`IgnoredAny`], validating it without building anything. The query is not evaluated.
+pub struct SerdeJsonIgnoredAny {}
+
+pub struct TraversalQuery(SimpleQuery);
+
+impl Implementation for SerdeJsonValue {
+    type Query = TraversalQuery;
+
+    type File = String;
+
+    type Error = SerdeJsonError;
+
+    type Result<'a> = u64;
+
+    fn id() -> &'static str {
+        "serde_json_value"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Count
+    }
+
+    fn new() -> Result<Self, Self::Error> {
+        Ok(SerdeJsonValue {})
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        Ok(fs::read_to_string(file_path)?)
+    }
+
+    fn compile_query(&self, query: &str) -> Result<Self::Query, Self::Error> {
+        TraversalQuery::parse(query)
+    }
+
+    fn run(&self, query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
+        let value: Value = serde_json::from_str(file)?;
+
+        Ok(query.evaluate(&value).len() as u64)
+    }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        let value: Value = serde_json::from_str(file)?;
+
+        Ok(ResultSummary::from_values(query.evaluate(&value)))
+    }
+}
+
+impl LoadRecord for SerdeJsonValue {
+    fn load_record(&self, record: &str) -> Result<Self::File, Self::Error> {
+        Ok(record.to_owned())
+    }
+}
+
+impl Implementation for SerdeJsonIgnoredAny {
+    type Query = ();
+
+    type File = String;
+
+    type Error = SerdeJsonError;
+
+    type Result<'a> = &'static str;
+
+    fn id() -> &'static str {
+        "serde_json_ignored_any"
+    }
+
+    fn result_type() -> ResultType {
+        ResultType::Count
+    }
+
+    fn evaluates_query() -> bool {
+        false
+    }
+
+    fn new() -> Result<Self, Self::Error> {
+        Ok(SerdeJsonIgnoredAny {})
+    }
+
+    fn load_file(&self, file_path: &str) -> Result<Self::File, Self::Error> {
+        Ok(fs::read_to_string(file_path)?)
+    }
+
+    fn compile_query(&self, _query: &str) -> Result<Self::Query, Self::Error> {
+        Ok(())
+    }
+
+    fn run(&self, _query: &Self::Query, file: &Self::File) -> Result<Self::Result<'_>, Self::Error> {
+        serde_json::from_str::<IgnoredAny>(file)?;
+
+        Ok("valid JSON")
+    }
+
+    fn summarize(&self, _query: &Self::Query, _file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        Err(SerdeJsonError::NotEvaluated)
+    }
+}
+
+impl LoadRecord for SerdeJsonIgnoredAny {
+    fn load_record(&self, record: &str) -> Result<Self::File, Self::Error> {
+        Ok(record.to_owned())
+    }
+}
+
+impl TraversalQuery {
+    fn parse(query: &str) -> Result<Self, SerdeJsonError> {
+        SimpleQuery::parse(query)
+            .map(Self)
+            .ok_or_else(|| SerdeJsonError::UnsupportedQuery(query.to_owned()))
+    }
+
+    fn evaluate<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+
+        for step in self.0.steps() {
+            let mut next = vec![];
+            for node in current {
+                match step {
+                    Step::Child(selector) => select(selector, node, &mut next),
+                    Step::Descendant(selector) => {
+                        let mut stack = vec![node];
+                        while let Some(descendant) = stack.pop() {
+                            select(selector, descendant, &mut next);
+                            match descendant {
+                                Value::Array(values) => stack.extend(values.iter().rev()),
+                                Value::Object(map) => stack.extend(map.values().rev()),
+                                _ => (),
+                            }
+                        }
+                    }
+                }
+            }
+            current = next;
+        }
+
+        current
+    }
+}
+
+fn select<'a>(selector: &Selector, node: &'a Value, selected: &mut Vec<&'a Value>) {
+    match (selector, node) {
+        (Selector::Name(name), Value::Object(map)) => selected.extend(map.get(name)),
+        (Selector::Index(index), Value::Array(values)) => selected.extend(values.get(*index)),
+        (Selector::Wildcard, Value::Array(values)) => selected.extend(values),
+        (Selector::Wildcard, Value::Object(map)) => selected.extend(map.values()),
+        _ => (),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SerdeJsonError {
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+    #[error("error parsing JSON with serde: '{0}'")]
+    SerdeError(#[from] serde_json::Error),
+    #[error("query '{0}' cannot be evaluated by traversing a serde_json Value")]
+    UnsupportedQuery(String),
+    #[error("the query is not evaluated by this baseline")]
+    NotEvaluated,
+}
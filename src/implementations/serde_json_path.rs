@@ -1,4 +1,8 @@
-use crate::framework::implementation::Implementation;
+use crate::framework::{
+    implementation::{Implementation, LoadRecord},
+    verification::ResultSummary,
+    ResultType,
+};
 use serde_json::Value;
 use serde_json_path::{JsonPath, NodeList, ParseError};
 use std::{
@@ -25,6 +29,10 @@ impl Implementation for SerdeJsonPath {
         "serde_json_path"
     }
 
+    fn result_type() -> ResultType {
+        ResultType::Full
+    }
+
     fn new() -> Result<Self, Self::Error> {
         Ok(SerdeJsonPath {})
     }
@@ -44,6 +52,18 @@ impl Implementation for SerdeJsonPath {
     fn run<'a>(&self, query: &Self::Query, file: &'a Self::File) -> Result<Self::Result<'a>, Self::Error> {
         Ok(SerdeJsonPathResult(query.query(file)))
     }
+
+    fn summarize(&self, query: &Self::Query, file: &Self::File) -> Result<ResultSummary, Self::Error> {
+        Ok(ResultSummary::from_values(query.query(file).all()))
+    }
+}
+
+impl LoadRecord for SerdeJsonPath {
+    fn load_record(&self, record: &str) -> Result<Self::File, Self::Error> {
+        let value: Value = serde_json::from_str(record)?;
+
+        Ok(value)
+    }
 }
 
 impl<'a> Display for SerdeJsonPathResult<'a> {
@@ -0,0 +1,153 @@
+//! The subset of JSONPath made of name, wildcard and non-negative index selectors, one per segment,
+//! in child or descendant segments, e.g. `$..products[*].name` or `$['a'][0]`.
+//!
+//! Used by engines that cannot take JSONPath directly: queries are evaluated by hand or translated
+//! into the engine's own language.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SimpleQuery {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Step {
+    Child(Selector),
+    Descendant(Selector),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Selector {
+    Name(String),
+    Wildcard,
+    Index(usize),
+}
+
+impl SimpleQuery {
+    /// Parse the query, or return `None` if it is not a simple query.
+    pub(crate) fn parse(query: &str) -> Option<Self> {
+        let mut rest = query.strip_prefix('$')?;
+        let mut steps = vec![];
+
+        while !rest.is_empty() {
+            let (descendant, after_dots) = if let Some(after) = rest.strip_prefix("..") {
+                (true, after)
+            } else if let Some(after) = rest.strip_prefix('.') {
+                (false, after)
+            } else {
+                (false, rest)
+            };
+
+            let (selector, after_selector) = if let Some(bracketed) = after_dots.strip_prefix('[') {
+                let end = bracketed.find(']')?;
+                (parse_bracketed(&bracketed[..end])?, &bracketed[end + 1..])
+            } else if after_dots.len() == rest.len() {
+                return None;
+            } else if let Some(after) = after_dots.strip_prefix('*') {
+                (Selector::Wildcard, after)
+            } else {
+                let end = after_dots.find(['.', '[']).unwrap_or(after_dots.len());
+                let name = &after_dots[..end];
+                if name.is_empty() || !name.chars().all(|c| c == '_' || c.is_alphanumeric()) {
+                    return None;
+                }
+                (Selector::Name(name.to_owned()), &after_dots[end..])
+            };
+
+            steps.push(if descendant {
+                Step::Descendant(selector)
+            } else {
+                Step::Child(selector)
+            });
+            rest = after_selector;
+        }
+
+        Some(Self { steps })
+    }
+
+    pub(crate) fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+}
+
+/// Parse the contents of a bracketed selector: `*`, a non-negative index, or a quoted name without escapes.
+fn parse_bracketed(contents: &str) -> Option<Selector> {
+    if contents == "*" {
+        return Some(Selector::Wildcard);
+    }
+    let is_index = contents.bytes().all(|b| b.is_ascii_digit()) && (contents == "0" || !contents.starts_with('0'));
+    if is_index {
+        return contents.parse().ok().map(Selector::Index);
+    }
+
+    ['\'', '"'].into_iter().find_map(|quote| {
+        let name = contents.strip_prefix(quote)?.strip_suffix(quote)?;
+        (!name.contains(['\\', quote])).then(|| Selector::Name(name.to_owned()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(query: &str) -> Option<Vec<Step>> {
+        SimpleQuery::parse(query).map(|q| q.steps().to_vec())
+    }
+
+    fn name(name: &str) -> Selector {
+        Selector::Name(name.to_owned())
+    }
+
+    #[test]
+    fn accepts_names_wildcards_and_indices() {
+        assert_eq!(
+            parse("$..a[*].b"),
+            Some(vec![
+                Step::Descendant(name("a")),
+                Step::Child(Selector::Wildcard),
+                Step::Child(name("b")),
+            ])
+        );
+        assert_eq!(
+            parse("$['a'][0]"),
+            Some(vec![Step::Child(name("a")), Step::Child(Selector::Index(0))])
+        );
+        assert_eq!(parse("$.*"), Some(vec![Step::Child(Selector::Wildcard)]));
+        assert_eq!(
+            parse("$..[\"b c\"]..[12]"),
+            Some(vec![
+                Step::Descendant(name("b c")),
+                Step::Descendant(Selector::Index(12))
+            ])
+        );
+        assert_eq!(parse("$"), Some(vec![]));
+    }
+
+    #[test]
+    fn rejects_filters() {
+        assert_eq!(parse("$[?@.a]"), None);
+        assert_eq!(parse("$..a[?(@.b == 1)]"), None);
+    }
+
+    #[test]
+    fn rejects_negative_and_malformed_indices() {
+        assert_eq!(parse("$[-1]"), None);
+        assert_eq!(parse("$[+1]"), None);
+        assert_eq!(parse("$[01]"), None);
+        assert_eq!(parse("$[1:3]"), None);
+    }
+
+    #[test]
+    fn rejects_escaped_names() {
+        assert_eq!(parse("$['a\\'b']"), None);
+        assert_eq!(parse("$['a\\nb']"), None);
+        assert_eq!(parse("$[\"a\\\"b\"]"), None);
+    }
+
+    #[test]
+    fn rejects_segments_without_a_dot_or_bracket() {
+        assert_eq!(parse("$a"), None);
+        assert_eq!(parse("a"), None);
+        assert_eq!(parse("$."), None);
+        assert_eq!(parse("$.a b"), None);
+    }
+}
@@ -1,6 +1,8 @@
 #[macro_export]
 macro_rules! benchsets {
     (name = $name:ident; config = $config:expr; targets = $( $target:path ),+ $(,)*) => {
+        $crate::__counting_allocator!();
+
         pub fn $name() {
             let mut criterion: ::criterion::Criterion<_> = $config
                 .configure_from_args();
@@ -13,6 +15,10 @@ macro_rules! benchsets {
                     }
                 }
             )+
+
+            if let Err(err) = $crate::framework::report::write_collected() {
+                ::std::panic!("error writing benchmark report: {}", err);
+            }
         }
 
         ::criterion::criterion_main! { $name }
@@ -25,3 +31,22 @@ macro_rules! benchsets {
         }
     }
 }
+
+/// Install [`CountingAllocator`](crate::framework::memory::CountingAllocator) as the global allocator
+/// if the `memory` feature of this crate is enabled.
+#[cfg(feature = "memory")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __counting_allocator {
+    () => {
+        #[global_allocator]
+        static ALLOCATOR: $crate::framework::memory::CountingAllocator = $crate::framework::memory::CountingAllocator;
+    };
+}
+
+#[cfg(not(feature = "memory"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __counting_allocator {
+    () => {};
+}
@@ -1,19 +1,68 @@
-use clap::{Parser, ValueEnum};
-use color_eyre::eyre::Result;
-use rsonpath_benchmarks::framework::implementation::Implementation;
+use clap::{Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::{eyre, Result};
+use rsonpath_benchmarks::dataset::{self, Compression, Dataset, DatasetStatus, DatasetTag};
+use rsonpath_benchmarks::framework::{comparison::Comparison, implementation::Implementation, report::Report};
+#[cfg(feature = "jsonpath_lib")]
+use rsonpath_benchmarks::implementations::jsonpath_lib::JsonpathLib;
+#[cfg(feature = "jsonski")]
+use rsonpath_benchmarks::implementations::jsonski::JsonSki;
+#[cfg(feature = "jsurfer")]
+use rsonpath_benchmarks::implementations::rust_jsurfer::JSurfer;
 use rsonpath_benchmarks::implementations::{
-    jsonpath_rust::JsonpathRust, rsonpath::RsonpathMmap, rust_jsurfer::JSurfer, serde_json_path::SerdeJsonPath,
+    external::{ExternalCommand, ExternalEngine},
+    jaq::Jaq,
+    jsonpath_rust::JsonpathRust,
+    rsonpath::{RsonpathBuffered, RsonpathMmap, RsonpathMmapCount},
+    serde_json_baseline::{SerdeJsonIgnoredAny, SerdeJsonValue},
+    serde_json_path::SerdeJsonPath,
 };
+use std::path::{Path, PathBuf};
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = Args::parse();
 
-    match args.engine {
-        ImplArg::Rsonpath => run(RsonpathMmap::new()?, &args.query, &args.file_path),
-        ImplArg::JSurfer => run(JSurfer::new()?, &args.query, &args.file_path),
-        ImplArg::JsonpathRust => run(JsonpathRust::new()?, &args.query, &args.file_path),
-        ImplArg::SerdeJsonPath => run(SerdeJsonPath::new()?, &args.query, &args.file_path),
+    match args.command {
+        Some(Command::Compare {
+            baseline,
+            current,
+            threshold,
+            allow_missing,
+        }) => compare(&baseline, &current, threshold, allow_missing),
+        Some(Command::Datasets { command }) => manage_datasets(command),
+        None => {
+            let query = args.query.expect("query is required without a subcommand");
+            let file_path = args.file_path.expect("file path is required without a subcommand");
+            let engine = args.engine.expect("engine is required without a subcommand");
+            let external_command = args
+                .external_command
+                .map(|program| ExternalCommand::new(program).args(args.external_arg));
+
+            run_engine(engine, external_command.as_ref(), &query, &file_path)
+        }
+    }
+}
+
+fn run_engine(engine: ImplArg, external_command: Option<&ExternalCommand>, query: &str, file_path: &str) -> Result<()> {
+    match engine {
+        ImplArg::Rsonpath => run(RsonpathMmap::collecting(), query, file_path),
+        ImplArg::RsonpathCount => run(RsonpathMmapCount::new()?, query, file_path),
+        ImplArg::RsonpathBuffered => run(RsonpathBuffered::collecting(), query, file_path),
+        #[cfg(feature = "jsurfer")]
+        ImplArg::JSurfer => run(JSurfer::new()?, query, file_path),
+        ImplArg::JsonpathRust => run(JsonpathRust::new()?, query, file_path),
+        ImplArg::SerdeJsonPath => run(SerdeJsonPath::new()?, query, file_path),
+        #[cfg(feature = "jsonpath_lib")]
+        ImplArg::JsonpathLib => run(JsonpathLib::new()?, query, file_path),
+        ImplArg::Jaq => run(Jaq::new()?, query, file_path),
+        #[cfg(feature = "jsonski")]
+        ImplArg::JsonSki => run(JsonSki::new()?, query, file_path),
+        ImplArg::SerdeJsonValue => run(SerdeJsonValue::new()?, query, file_path),
+        ImplArg::SerdeJsonIgnoredAny => run(SerdeJsonIgnoredAny::new()?, query, file_path),
+        ImplArg::External => {
+            let command = external_command.ok_or_else(|| eyre!("the external engine requires --external-command"))?;
+            run(ExternalEngine::spawn(command)?, query, file_path)
+        }
     }
 }
 
@@ -28,26 +77,241 @@ fn run<I: Implementation>(imp: I, query_str: &str, path_str: &str) -> Result<()>
     Ok(())
 }
 
+fn compare(baseline: &Path, current: &Path, threshold: f64, allow_missing: bool) -> Result<()> {
+    let baseline = Report::load(baseline)?;
+    let current = Report::load(current)?;
+    let mut comparison = Comparison::new(&baseline, &current, threshold / 100.0);
+    if allow_missing {
+        comparison = comparison.allow_missing();
+    }
+
+    print!("{comparison}");
+
+    if comparison.has_regressions() {
+        Err(eyre!("performance regressed by more than {threshold}%"))
+    } else if comparison.fails() {
+        Err(eyre!("targets of the baseline are missing from the current report"))
+    } else {
+        Ok(())
+    }
+}
+
+fn manage_datasets(command: DatasetsCommand) -> Result<()> {
+    match command {
+        DatasetsCommand::List { tag } => {
+            let datasets = match tag {
+                Some(tag) => dataset::with_tag(tag),
+                None => dataset::all(),
+            };
+            for d in datasets {
+                let tags = d.tags().iter().map(|t| t.name()).collect::<Vec<_>>().join(",");
+                let present = if d.is_present() {
+                    "present"
+                } else if d.is_present_compressed() {
+                    "compressed"
+                } else {
+                    "missing"
+                };
+                println!(
+                    "{:<24} {:<16} {:<10} {:<20} {}",
+                    d.name(),
+                    d.source().kind(),
+                    present,
+                    tags,
+                    d.path()
+                );
+            }
+        }
+        DatasetsCommand::Fetch { names } => {
+            for d in select_datasets(&names)? {
+                let size = d.fetch()?;
+                println!("{}: ok ({size} bytes)", d.name());
+            }
+        }
+        DatasetsCommand::Verify { names } => {
+            let mut failed = 0;
+            for d in select_datasets(&names)? {
+                match d.verify()? {
+                    DatasetStatus::Valid { size_in_bytes } => println!("{}: ok ({size_in_bytes} bytes)", d.name()),
+                    DatasetStatus::Missing => {
+                        failed += 1;
+                        println!("{}: missing", d.name());
+                    }
+                    DatasetStatus::WrongSize {
+                        expected_size_in_bytes,
+                        actual_size_in_bytes,
+                    } => {
+                        failed += 1;
+                        println!(
+                            "{}: size mismatch (expected {expected_size_in_bytes} bytes, actual {actual_size_in_bytes})",
+                            d.name()
+                        );
+                    }
+                    DatasetStatus::Corrupted { actual_checksum } => {
+                        failed += 1;
+                        println!(
+                            "{}: checksum mismatch (expected {}, actual {actual_checksum})",
+                            d.name(),
+                            d.checksum_hex()
+                        );
+                    }
+                }
+            }
+            if failed > 0 {
+                return Err(eyre!("{failed} datasets are missing or corrupted"));
+            }
+        }
+        DatasetsCommand::Compress {
+            names,
+            format,
+            remove_json,
+        } => {
+            for d in select_datasets(&names)? {
+                let size = d.compress(format)?;
+                println!("{}: ok ({size} bytes in {})", d.name(), d.compressed_path(format));
+                if remove_json && d.remove_json()? {
+                    println!("{}: JSON file removed", d.name());
+                }
+            }
+        }
+        DatasetsCommand::Clean { names } => {
+            for d in select_datasets(&names)? {
+                if d.clean()? {
+                    println!("{}: removed", d.name());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn select_datasets(names: &[String]) -> Result<Vec<Dataset>> {
+    if names.is_empty() || names.iter().any(|n| n == "all") {
+        Ok(dataset::all())
+    } else {
+        Ok(names.iter().map(|n| dataset::by_name(n)).collect::<Result<_, _>>()?)
+    }
+}
+
 #[derive(Parser, Debug)]
-#[clap(author, version, about)]
+#[clap(
+    author,
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
     /// JSONPath query to run against the input JSON.
-    query: String,
+    #[clap(required = true)]
+    query: Option<String>,
     /// Input JSON file to query.
-    file_path: String,
+    #[clap(required = true)]
+    file_path: Option<String>,
     /// JSONPath implementation to use for evaluating the query.
-    #[clap(short, long, value_enum)]
-    engine: ImplArg,
+    #[clap(short, long, value_enum, required = true)]
+    engine: Option<ImplArg>,
+    /// Program to start for the external engine.
+    #[clap(long, required_if_eq("engine", "external"))]
+    external_command: Option<PathBuf>,
+    /// Argument passed to the external engine's program, can be repeated.
+    #[clap(long, allow_hyphen_values = true)]
+    external_arg: Vec<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compare a benchmark report against a baseline report and fail if any target regressed or is missing.
+    Compare {
+        /// Report with the reference results, e.g. from the last release.
+        baseline: PathBuf,
+        /// Report with the results to check.
+        current: PathBuf,
+        /// Maximum allowed slowdown of a target, in percent.
+        #[clap(short, long, default_value_t = 5.0)]
+        threshold: f64,
+        /// Do not fail if targets of the baseline are missing from the current report.
+        #[clap(long)]
+        allow_missing: bool,
+    },
+    /// Manage the datasets used by the benchmarks in `./data`.
+    Datasets {
+        #[clap(subcommand)]
+        command: DatasetsCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DatasetsCommand {
+    /// List all known datasets and whether they are present on disk.
+    List {
+        /// Only list datasets with the given tag (pison, nativejson, crossref, small, generated).
+        #[clap(short, long)]
+        tag: Option<DatasetTag>,
+    },
+    /// Download datasets that are missing or corrupted and verify their checksums.
+    Fetch {
+        /// Names of datasets to fetch, or "all".
+        #[clap(required = true)]
+        names: Vec<String>,
+    },
+    /// Verify checksums of datasets on disk without downloading anything.
+    Verify {
+        /// Names of datasets to verify. Defaults to all datasets.
+        names: Vec<String>,
+    },
+    /// Store compressed copies of datasets next to their JSON files.
+    ///
+    /// When a JSON file is missing, it is restored from a compressed copy instead of being downloaded.
+    Compress {
+        /// Names of datasets to compress, or "all".
+        #[clap(required = true)]
+        names: Vec<String>,
+        /// Compression format, gzip or zstd (requires the `zstd` feature).
+        #[clap(short, long, default_value = "gzip")]
+        format: Compression,
+        /// Remove the JSON files afterwards, keeping only the compressed copies.
+        #[clap(long)]
+        remove_json: bool,
+    },
+    /// Remove downloaded dataset files.
+    Clean {
+        /// Names of datasets to remove, or "all".
+        #[clap(required = true)]
+        names: Vec<String>,
+    },
 }
 
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 enum ImplArg {
     /// Use rsonpath.
     Rsonpath,
+    /// Use rsonpath, printing only the number of matches.
+    RsonpathCount,
+    /// Use rsonpath reading the file through a buffered reader instead of memory-mapping it.
+    RsonpathBuffered,
     /// Use JSurfer via JNI.
+    #[cfg(feature = "jsurfer")]
     JSurfer,
     /// Use the jsonpath-rust crate.
     JsonpathRust,
     /// Use the serde_json_path crate.
     SerdeJsonPath,
+    /// Use the jsonpath_lib crate.
+    #[cfg(feature = "jsonpath_lib")]
+    JsonpathLib,
+    /// Use jaq, translating a simple query into a jq filter.
+    Jaq,
+    /// Use JSONSki via FFI.
+    #[cfg(feature = "jsonski")]
+    JsonSki,
+    /// Parse with serde_json into a Value and count matches of a simple query by traversing it.
+    SerdeJsonValue,
+    /// Only check that the input parses with serde_json, ignoring the query.
+    SerdeJsonIgnoredAny,
+    /// Start --external-command and talk to it over the external engine protocol.
+    External,
 }
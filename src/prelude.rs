@@ -1,5 +1,6 @@
 pub use crate::benchsets;
 pub use crate::dataset;
+pub use crate::framework::family::BenchsetFamily;
 pub use crate::framework::BenchmarkError;
 pub use crate::framework::Benchset;
 pub use crate::framework::{BenchTarget, ResultType};